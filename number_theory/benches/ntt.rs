@@ -0,0 +1,46 @@
+use std::time::{Duration, Instant};
+
+use lambdaworks_math::elliptic_curve::short_weierstrass::curves::bls12_381::default_types::FrElement;
+use lambdaworks_math::polynomial::Polynomial;
+use lambdaworks_math::unsigned_integer::element::UnsignedInteger;
+use number_theory::NttContext;
+
+const NUM_LIMBS: usize = 4;
+const ITERATIONS: u32 = 5;
+
+fn time<T>(f: impl Fn() -> T) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        std::hint::black_box(f());
+    }
+    start.elapsed() / ITERATIONS
+}
+
+fn main() {
+    // BLS12-381 scalar field, with 2-adicity 32, as used by the KZG commitments.
+    let modulus = UnsignedInteger::<NUM_LIMBS>::from_hex_unchecked(
+        "73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001"
+    );
+    let ntt = NttContext::new(&modulus).unwrap();
+
+    println!("{:>6} {:>14} {:>14} {:>14} {:>14}", "len", "ntt mul", "schoolbook mul", "ntt div", "long division");
+    // lambdaworks' long division becomes very slow beyond a few hundred coefficients
+    for log_len in 4..=9 {
+        let len = 1 << log_len;
+        let a: Vec<UnsignedInteger<NUM_LIMBS>> = (0..2 * len as u64)
+            .map(|i| UnsignedInteger::from_u64(i.wrapping_mul(0x9e3779b97f4a7c15)))
+            .collect();
+        let b: Vec<UnsignedInteger<NUM_LIMBS>> = (0..len as u64)
+            .map(|i| UnsignedInteger::from_u64((i + 1).wrapping_mul(0xb7e151628aed2a6b)))
+            .collect();
+        let a_poly = Polynomial::new(&a.iter().map(|c| FrElement::new(*c)).collect::<Vec<_>>());
+        let b_poly = Polynomial::new(&b.iter().map(|c| FrElement::new(*c)).collect::<Vec<_>>());
+
+        // products of two polynomials with len coefficients, and 2 len by len divisions
+        let ntt_mul = time(|| ntt.multiply(&a[..len], &b));
+        let schoolbook_mul = time(|| Polynomial::new(&a_poly.coefficients()[..len]).mul_with_ref(&b_poly));
+        let ntt_div = time(|| ntt.divide(&a, &b));
+        let long_division = time(|| a_poly.clone().long_division_with_remainder(&b_poly));
+        println!("{:>6} {:>14?} {:>14?} {:>14?} {:>14?}", len, ntt_mul, schoolbook_mul, ntt_div, long_division);
+    }
+}
@@ -0,0 +1,51 @@
+use std::time::Instant;
+
+use lambdaworks_math::unsigned_integer::element::UnsignedInteger;
+use number_theory::{
+    power_mod, power_mod_fixed_window, power_mod_window,
+    window::{fixed_window_pow, sliding_window_pow},
+};
+
+const NUM_LIMBS: usize = 4;
+const ITERATIONS: u32 = 20;
+
+// Counts the multiplications (squarings included) spent by an exponentiation
+// by replacing them with additions of integers modulo 2^64.
+// A sliding window of 1 bit is the square-and-multiply of power_mod.
+fn count_sliding<const W: usize>(exp: &UnsignedInteger<NUM_LIMBS>) -> usize {
+    let mut count = 0;
+    sliding_window_pow::<_, W, NUM_LIMBS>(&1_u64, exp, &0, |a, b| { count += 1; a.wrapping_add(*b) });
+    count
+}
+
+fn count_fixed<const W: usize>(exp: &UnsignedInteger<NUM_LIMBS>) -> usize {
+    let mut count = 0;
+    fixed_window_pow::<_, W, NUM_LIMBS>(&1_u64, exp, &0, |a, b| { count += 1; a.wrapping_add(*b) });
+    count
+}
+
+fn time(name: &str, count: usize, f: impl Fn() -> UnsignedInteger<NUM_LIMBS>) {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        std::hint::black_box(f());
+    }
+    println!("{:<26} {:>5} multiplications {:>12?} per exponentiation", name, count, start.elapsed() / ITERATIONS);
+}
+
+fn main() {
+    // BLS12-381 subgroup order and a full-width exponent.
+    let modulus = UnsignedInteger::<NUM_LIMBS>::from_hex_unchecked(
+        "73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001"
+    );
+    let base = UnsignedInteger::<NUM_LIMBS>::from_u64(5);
+    let exp = modulus - UnsignedInteger::from_u64(2);
+
+    time("power_mod", count_sliding::<1>(&exp), || power_mod(base, exp, &modulus));
+    time("power_mod_window::<2>", count_sliding::<2>(&exp), || power_mod_window::<2, NUM_LIMBS>(base, exp, &modulus));
+    time("power_mod_window::<3>", count_sliding::<3>(&exp), || power_mod_window::<3, NUM_LIMBS>(base, exp, &modulus));
+    time("power_mod_window::<4>", count_sliding::<4>(&exp), || power_mod_window::<4, NUM_LIMBS>(base, exp, &modulus));
+    time("power_mod_window::<5>", count_sliding::<5>(&exp), || power_mod_window::<5, NUM_LIMBS>(base, exp, &modulus));
+    time("power_mod_window::<6>", count_sliding::<6>(&exp), || power_mod_window::<6, NUM_LIMBS>(base, exp, &modulus));
+    time("power_mod_fixed_window::<4>", count_fixed::<4>(&exp), || power_mod_fixed_window::<4, NUM_LIMBS>(base, exp, &modulus));
+    time("power_mod_fixed_window::<5>", count_fixed::<5>(&exp), || power_mod_fixed_window::<5, NUM_LIMBS>(base, exp, &modulus));
+}
@@ -0,0 +1,139 @@
+//
+// Multiplicative arithmetic functions. Each one is computed from the
+// factorization of n, given as (prime, exponent) pairs with distinct primes,
+// or from `factor(n)` when the factorization is not known.
+//
+use lambdaworks_math::unsigned_integer::element::UnsignedInteger;
+
+use crate::{factor::factor, lcm};
+
+/// p^e, panicking on overflow.
+fn prime_power<const NUM_LIMBS: usize>(prime: &UnsignedInteger<NUM_LIMBS>, exponent: u32) -> UnsignedInteger<NUM_LIMBS> {
+    (0..exponent).fold(UnsignedInteger::from_u64(1), |power, _| power * prime)
+}
+
+/// Euler's totient of the factored integer: prod p^(e - 1) (p - 1).
+/// Entries with e = 0 stand for p^0 = 1 and are skipped.
+pub fn euler_phi_from_factorization<const NUM_LIMBS: usize>(
+        factorization: &[(UnsignedInteger<NUM_LIMBS>, u32)],
+    ) -> UnsignedInteger<NUM_LIMBS> {
+    let one = UnsignedInteger::<NUM_LIMBS>::from_u64(1);
+    factorization.iter().filter(|(_, exponent)| *exponent > 0).fold(one, |phi, (prime, exponent)| {
+        phi * prime_power(prime, exponent - 1) * (*prime - one)
+    })
+}
+
+/// Carmichael's function of the factored integer, the exponent of its multiplicative group:
+/// the lcm of p^(e - 1) (p - 1) over odd primes, with 2^(e - 2) instead for 2^e, e >= 3.
+/// Entries with e = 0 are skipped.
+pub fn carmichael_lambda_from_factorization<const NUM_LIMBS: usize>(
+        factorization: &[(UnsignedInteger<NUM_LIMBS>, u32)],
+    ) -> UnsignedInteger<NUM_LIMBS> {
+    let one = UnsignedInteger::<NUM_LIMBS>::from_u64(1);
+    let two = UnsignedInteger::<NUM_LIMBS>::from_u64(2);
+    factorization.iter().filter(|(_, exponent)| *exponent > 0).fold(one, |lambda, (prime, exponent)| {
+        let lambda_of_power = if *prime == two && *exponent >= 3 {
+            prime_power(prime, exponent - 2)
+        } else {
+            prime_power(prime, exponent - 1) * (*prime - one)
+        };
+        lcm(&lambda, &lambda_of_power)
+    })
+}
+
+/// Number of divisors of the factored integer: prod (e + 1).
+pub fn divisor_count_from_factorization<const NUM_LIMBS: usize>(
+        factorization: &[(UnsignedInteger<NUM_LIMBS>, u32)],
+    ) -> u64 {
+    factorization.iter().map(|(_, exponent)| *exponent as u64 + 1).product()
+}
+
+/// Sum of the divisors of the factored integer: prod (1 + p + ... + p^e).
+/// Panics if it overflows.
+pub fn divisor_sum_from_factorization<const NUM_LIMBS: usize>(
+        factorization: &[(UnsignedInteger<NUM_LIMBS>, u32)],
+    ) -> UnsignedInteger<NUM_LIMBS> {
+    let one = UnsignedInteger::<NUM_LIMBS>::from_u64(1);
+    factorization.iter().fold(one, |sigma, (prime, exponent)| {
+        // 1 + p + ... + p^e by Horner's rule
+        let sum = (0..*exponent).fold(one, |sum, _| sum * prime + one);
+        sigma * sum
+    })
+}
+
+/// Euler's totient of n > 0, the number of integers in [1, n] coprime to n.
+pub fn euler_phi<const NUM_LIMBS: usize>(n: &UnsignedInteger<NUM_LIMBS>) -> UnsignedInteger<NUM_LIMBS> {
+    euler_phi_from_factorization(&factor(n))
+}
+
+/// Carmichael's function of n > 0, the smallest m with a^m = 1 (mod n) for every a coprime to n.
+pub fn carmichael_lambda<const NUM_LIMBS: usize>(n: &UnsignedInteger<NUM_LIMBS>) -> UnsignedInteger<NUM_LIMBS> {
+    carmichael_lambda_from_factorization(&factor(n))
+}
+
+/// Number of divisors of n > 0.
+pub fn divisor_count<const NUM_LIMBS: usize>(n: &UnsignedInteger<NUM_LIMBS>) -> u64 {
+    divisor_count_from_factorization(&factor(n))
+}
+
+/// Sum of the divisors of n > 0. Panics if it overflows.
+pub fn divisor_sum<const NUM_LIMBS: usize>(n: &UnsignedInteger<NUM_LIMBS>) -> UnsignedInteger<NUM_LIMBS> {
+    divisor_sum_from_factorization(&factor(n))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type U = UnsignedInteger<2>;
+
+    fn gcd_u64(a: u64, b: u64) -> u64 {
+        if b == 0 { a } else { gcd_u64(b, a % b) }
+    }
+
+    #[test]
+    fn arithmetic_functions_match_brute_force() {
+        for n in 1..300_u64 {
+            let un = U::from_u64(n);
+            let phi = (1..=n).filter(|&a| gcd_u64(a, n) == 1).count() as u64;
+            let divisors: Vec<u64> = (1..=n).filter(|d| n % d == 0).collect();
+            // lcm of the orders of the units
+            let lambda = (1..=n).filter(|&a| gcd_u64(a, n) == 1).fold(1, |lambda, a| {
+                let mut x = a % n;
+                let mut order = 1;
+                while x != 1 % n {
+                    x = x * a % n;
+                    order += 1;
+                }
+                lambda / gcd_u64(lambda, order) * order
+            });
+
+            assert_eq!(euler_phi(&un), U::from_u64(phi));
+            assert_eq!(carmichael_lambda(&un), U::from_u64(lambda));
+            assert_eq!(divisor_count(&un), divisors.len() as u64);
+            assert_eq!(divisor_sum(&un), U::from_u64(divisors.iter().sum()));
+        }
+    }
+
+    #[test]
+    fn rsa_modulus_from_factorization() {
+        // n = p q with p - 1 = 2 * 3 * 5 * 11 and q - 1 = 2^2 * 3^2 * 11, so gcd(p - 1, q - 1) = 66
+        let (p, q) = (U::from_u64(331), U::from_u64(397));
+        let factorization = [(p, 1), (q, 1)];
+        assert_eq!(euler_phi_from_factorization(&factorization), U::from_u64(330 * 396));
+        assert_eq!(carmichael_lambda_from_factorization(&factorization), U::from_u64(330 * 396 / 66));
+        assert_eq!(divisor_count_from_factorization(&factorization), 4);
+        assert_eq!(divisor_sum_from_factorization(&factorization), U::from_u64(332 * 398));
+    }
+
+    #[test]
+    fn zero_exponents_are_skipped() {
+        // 2^3 * 5^0 * 7 = 56
+        let factorization = [(U::from_u64(2), 3), (U::from_u64(5), 0), (U::from_u64(7), 1)];
+        assert_eq!(euler_phi_from_factorization(&factorization), euler_phi(&U::from_u64(56)));
+        assert_eq!(carmichael_lambda_from_factorization(&factorization), carmichael_lambda(&U::from_u64(56)));
+        assert_eq!(divisor_count_from_factorization(&factorization), divisor_count(&U::from_u64(56)));
+        assert_eq!(divisor_sum_from_factorization(&factorization), divisor_sum(&U::from_u64(56)));
+        assert_eq!(euler_phi_from_factorization(&[(U::from_u64(3), 0)]), U::from_u64(1));
+    }
+}
@@ -0,0 +1,156 @@
+use lambdaworks_math::unsigned_integer::element::UnsignedInteger;
+
+use crate::{div_wide, window::sliding_window_pow, LIMB_SIZE_BIT};
+
+/// Bits (hi * 2^(64 * NUM_LIMBS) + lo) >> shift that fit in NUM_LIMBS limbs.
+fn shr_wide<const NUM_LIMBS: usize>(
+        hi: &UnsignedInteger<NUM_LIMBS>,
+        lo: &UnsignedInteger<NUM_LIMBS>,
+        shift: usize,
+    ) -> UnsignedInteger<NUM_LIMBS> {
+    let width = NUM_LIMBS * LIMB_SIZE_BIT;
+    if shift == 0 {
+        *lo
+    } else if shift >= width {
+        *hi >> (shift - width)
+    } else {
+        (*lo >> shift) | (*hi << (width - shift))
+    }
+}
+
+/// Precomputed constants for Barrett reduction modulo a runtime modulus m of b bits,
+/// odd or even, with mu = floor(4^b / m).
+/// Elements are represented in standard form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BarrettContext<const NUM_LIMBS: usize> {
+    modulus: UnsignedInteger<NUM_LIMBS>,
+    mu: UnsignedInteger<NUM_LIMBS>,
+    bits: usize,
+}
+
+impl<const NUM_LIMBS: usize> BarrettContext<NUM_LIMBS> {
+    /// Returns `None` if the modulus is zero or has more than 64 * NUM_LIMBS - 2 bits,
+    /// which the intermediate values need to fit.
+    pub fn new(modulus: &UnsignedInteger<NUM_LIMBS>) -> Option<Self> {
+        let zero = UnsignedInteger::<NUM_LIMBS>::from_u64(0);
+        let one = UnsignedInteger::<NUM_LIMBS>::from_u64(1);
+
+        let bits = modulus.bits_le();
+        if bits == 0 || bits + 2 > NUM_LIMBS * LIMB_SIZE_BIT {
+            return None;
+        }
+        // 4^b = hi * 2^(64 * NUM_LIMBS) + lo, and mu < 2^(b + 1) fits
+        let width = NUM_LIMBS * LIMB_SIZE_BIT;
+        let (hi, lo) = if 2 * bits < width { (zero, one << (2 * bits)) } else { (one << (2 * bits - width), zero) };
+        let (mu, _) = div_wide(&hi, &lo, modulus)?;
+        Some(Self { modulus: *modulus, mu, bits })
+    }
+
+    pub fn modulus(&self) -> &UnsignedInteger<NUM_LIMBS> {
+        &self.modulus
+    }
+
+    /// Reduces the double-width integer x = hi * 2^(64 * NUM_LIMBS) + lo modulo m,
+    /// for x < 4^b, such as a product of two residues.
+    pub fn reduce(
+            &self,
+            hi: &UnsignedInteger<NUM_LIMBS>,
+            lo: &UnsignedInteger<NUM_LIMBS>,
+        ) -> UnsignedInteger<NUM_LIMBS> {
+        //
+        // q = floor(floor(x / 2^(b - 1)) mu / 2^(b + 1)) is at most 2 below
+        // floor(x / m), so r = x - q m is in [0, 3m). Since 3m < 2^(64 * NUM_LIMBS),
+        // r can be computed from the low limbs of x and q m alone.
+        //
+        let q1 = shr_wide(hi, lo, self.bits - 1);
+        let (q2_hi, q2_lo) = UnsignedInteger::mul(&q1, &self.mu);
+        let q3 = shr_wide(&q2_hi, &q2_lo, self.bits + 1);
+        let (_, q3_m) = UnsignedInteger::mul(&q3, &self.modulus);
+        let (mut r, _) = UnsignedInteger::sub(lo, &q3_m);
+        while r >= self.modulus {
+            r = r - self.modulus;
+        }
+        r
+    }
+
+    /// Product a * b mod m of residues a, b < m.
+    pub fn mul_mod(
+            &self,
+            a: &UnsignedInteger<NUM_LIMBS>,
+            b: &UnsignedInteger<NUM_LIMBS>,
+        ) -> UnsignedInteger<NUM_LIMBS> {
+        let (hi, lo) = UnsignedInteger::mul(a, b);
+        self.reduce(&hi, &lo)
+    }
+
+    /// Raises `base` to `exp` modulo m, with a sliding window of W bits.
+    pub fn pow_window<const W: usize>(
+            &self,
+            base: &UnsignedInteger<NUM_LIMBS>,
+            exp: &UnsignedInteger<NUM_LIMBS>,
+        ) -> UnsignedInteger<NUM_LIMBS> {
+        let (_, base) = base.div_rem(&self.modulus);
+        let (_, one) = UnsignedInteger::from_u64(1).div_rem(&self.modulus);
+        sliding_window_pow::<_, W, NUM_LIMBS>(&base, exp, &one, |a, b| self.mul_mod(a, b))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mul_mod, power_mod};
+    use rand::prelude::*;
+
+    #[test]
+    fn invalid_moduli_are_rejected() {
+        assert!(BarrettContext::new(&UnsignedInteger::<2>::from_u64(0)).is_none());
+        assert!(BarrettContext::new(&UnsignedInteger::<2>::from_u128(u128::MAX >> 1)).is_none());
+        assert!(BarrettContext::new(&UnsignedInteger::<2>::from_u128(u128::MAX >> 2)).is_some());
+    }
+
+    #[test]
+    fn mul_mod_matches_reference() {
+        let mut rng = rand::thread_rng();
+        let moduli = [
+            UnsignedInteger::<2>::from_u64(1),
+            UnsignedInteger::<2>::from_u64(2),
+            UnsignedInteger::<2>::from_u64(2147483648),
+            UnsignedInteger::<2>::from_u64(1_000_000_006),
+            UnsignedInteger::<2>::from_u128(1 << 64),
+            UnsignedInteger::<2>::from_u128((u128::MAX >> 2) - 1),
+        ];
+        for modulus in moduli {
+            let ctx = BarrettContext::new(&modulus).unwrap();
+            for _ in 0..200 {
+                let a = UnsignedInteger::from_u128(rng.gen::<u128>()).div_rem(&modulus).1;
+                let b = UnsignedInteger::from_u128(rng.gen::<u128>()).div_rem(&modulus).1;
+                assert_eq!(ctx.mul_mod(&a, &b), mul_mod(&a, &b, &modulus));
+            }
+            let minus_one = modulus - UnsignedInteger::from_u64(1);
+            assert_eq!(ctx.mul_mod(&minus_one, &minus_one), mul_mod(&minus_one, &minus_one, &modulus));
+        }
+    }
+
+    #[test]
+    fn pow_matches_power_mod() {
+        // the even modulus of power_mod's own test
+        let modulus = UnsignedInteger::<2>::from_u64(2147483648);
+        let ctx = BarrettContext::new(&modulus).unwrap();
+        let base = UnsignedInteger::<2>::from_u64(3);
+        for e in [0, 1, 2, 31, 32, 1000, 2147483647] {
+            let exp = UnsignedInteger::from_u64(e);
+            assert_eq!(ctx.pow_window::<4>(&base, &exp), power_mod(base, exp, &modulus));
+        }
+
+        let modulus = UnsignedInteger::<4>::from_hex_unchecked(
+            "3a0c92075c0dbf3b8acbc5f96ce3f0ad2f3e8f3a2c2b6c4e1ee4f0b6a1d2c3e4"
+        );
+        let ctx = BarrettContext::new(&modulus).unwrap();
+        let base = UnsignedInteger::<4>::from_hex_unchecked("e4840ac57f86f5e293b1d67bc8de5d9a");
+        let exp = UnsignedInteger::<4>::from_hex_unchecked(
+            "f1e2d3c4b5a6978801234567890abcdef0fedcba9876543210aabbccddeeff00"
+        );
+        assert_eq!(ctx.pow_window::<1>(&base, &exp), power_mod(base, exp, &modulus));
+        assert_eq!(ctx.pow_window::<5>(&base, &exp), power_mod(base, exp, &modulus));
+    }
+}
@@ -0,0 +1,161 @@
+use lambdaworks_math::unsigned_integer::element::UnsignedInteger;
+
+use crate::{div_wide, isqrt};
+
+/// Partial quotients a_0, a_1, ... of the finite continued fraction of a / b,
+/// obtained from the quotients of Euclid's algorithm.
+#[derive(Debug, Clone)]
+pub struct ContinuedFraction<const NUM_LIMBS: usize> {
+    a: UnsignedInteger<NUM_LIMBS>,
+    b: UnsignedInteger<NUM_LIMBS>,
+}
+
+impl<const NUM_LIMBS: usize> ContinuedFraction<NUM_LIMBS> {
+    /// Expansion of a / b. Panics if b = 0.
+    pub fn new(a: &UnsignedInteger<NUM_LIMBS>, b: &UnsignedInteger<NUM_LIMBS>) -> Self {
+        assert!(*b != UnsignedInteger::from_u64(0), "zero denominator");
+        Self { a: *a, b: *b }
+    }
+}
+
+impl<const NUM_LIMBS: usize> Iterator for ContinuedFraction<NUM_LIMBS> {
+    type Item = UnsignedInteger<NUM_LIMBS>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.b == UnsignedInteger::from_u64(0) {
+            return None;
+        }
+        let (quotient, remainder) = self.a.div_rem(&self.b);
+        (self.a, self.b) = (self.b, remainder);
+        Some(quotient)
+    }
+}
+
+/// Convergents h_i / k_i of a / b as (numerator, denominator) pairs, from
+/// h_i = a_i h_(i-1) + h_(i-2) and k_i = a_i k_(i-1) + k_(i-2). The last one is
+/// a / b in lowest terms, so they never overflow.
+#[derive(Debug, Clone)]
+pub struct Convergents<const NUM_LIMBS: usize> {
+    quotients: ContinuedFraction<NUM_LIMBS>,
+    /// (h_(i-1), h_(i-2)) and (k_(i-1), k_(i-2))
+    numerators: (UnsignedInteger<NUM_LIMBS>, UnsignedInteger<NUM_LIMBS>),
+    denominators: (UnsignedInteger<NUM_LIMBS>, UnsignedInteger<NUM_LIMBS>),
+}
+
+impl<const NUM_LIMBS: usize> Convergents<NUM_LIMBS> {
+    /// Convergents of a / b. Panics if b = 0.
+    pub fn new(a: &UnsignedInteger<NUM_LIMBS>, b: &UnsignedInteger<NUM_LIMBS>) -> Self {
+        let zero = UnsignedInteger::<NUM_LIMBS>::from_u64(0);
+        let one = UnsignedInteger::<NUM_LIMBS>::from_u64(1);
+        Self {
+            quotients: ContinuedFraction::new(a, b),
+            numerators: (one, zero),
+            denominators: (zero, one),
+        }
+    }
+}
+
+impl<const NUM_LIMBS: usize> Iterator for Convergents<NUM_LIMBS> {
+    type Item = (UnsignedInteger<NUM_LIMBS>, UnsignedInteger<NUM_LIMBS>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let quotient = self.quotients.next()?;
+        let h = quotient * self.numerators.0 + self.numerators.1;
+        let k = quotient * self.denominators.0 + self.denominators.1;
+        self.numerators = (h, self.numerators.0);
+        self.denominators = (k, self.denominators.0);
+        Some((h, k))
+    }
+}
+
+/// Wiener's attack on an RSA public key (e, n) with e d = 1 (mod phi(n)).
+/// When d < n^(1/4) / 3, k / d is a convergent of e / n for the k with
+/// e d - 1 = k phi(n). Each convergent gives a candidate phi(n), which is
+/// right when p + q = n - phi(n) + 1 and n are the sum and product of two integers.
+/// Returns d.
+pub fn wiener_attack<const NUM_LIMBS: usize>(
+        e: &UnsignedInteger<NUM_LIMBS>,
+        n: &UnsignedInteger<NUM_LIMBS>,
+    ) -> Option<UnsignedInteger<NUM_LIMBS>> {
+    let zero = UnsignedInteger::<NUM_LIMBS>::from_u64(0);
+    let one = UnsignedInteger::<NUM_LIMBS>::from_u64(1);
+
+    Convergents::new(e, n).find_map(|(k, d)| {
+        if k == zero {
+            return None;
+        }
+        // phi = (e d - 1) / k, where e d takes up to two widths
+        let (hi, lo) = UnsignedInteger::mul(e, &d);
+        let (lo, borrow) = UnsignedInteger::sub(&lo, &one);
+        let hi = if borrow { hi - one } else { hi };
+        let (phi, remainder) = div_wide(&hi, &lo, &k)?;
+        if remainder != zero || phi >= *n {
+            return None;
+        }
+        // p and q are t -+ delta, with t = (p + q) / 2 and delta^2 = t^2 - n
+        let s = *n - phi + one;
+        if s.limbs[NUM_LIMBS - 1] & 1 == 1 {
+            return None;
+        }
+        let t = s >> 1;
+        let (hi, lo) = UnsignedInteger::mul(&t, &t);
+        let (delta_squared, borrow) = UnsignedInteger::sub(&lo, n);
+        if hi != (if borrow { one } else { zero }) {
+            return None;
+        }
+        let delta = isqrt(&delta_squared);
+        if delta * delta != delta_squared || delta >= t {
+            return None;
+        }
+        let (hi, product) = UnsignedInteger::mul(&(t - delta), &(t + delta));
+        (hi == zero && product == *n).then_some(d)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type U = UnsignedInteger<4>;
+
+    #[test]
+    fn expansion_and_convergents() {
+        // 415 / 93 = [4; 2, 6, 7]
+        let (a, b) = (U::from_u64(415), U::from_u64(93));
+        let quotients: Vec<U> = ContinuedFraction::new(&a, &b).collect();
+        assert_eq!(quotients, [4, 2, 6, 7].map(U::from_u64));
+        let convergents: Vec<(U, U)> = Convergents::new(&a, &b).collect();
+        assert_eq!(convergents, [(4, 1), (9, 2), (58, 13), (415, 93)].map(|(h, k)| (U::from_u64(h), U::from_u64(k))));
+
+        // the last convergent is in lowest terms, and a / b < 1 starts with 0
+        let last = Convergents::new(&U::from_u64(12), &U::from_u64(18)).last();
+        assert_eq!(last, Some((U::from_u64(2), U::from_u64(3))));
+        assert_eq!(ContinuedFraction::new(&U::from_u64(2), &U::from_u64(3)).next(), Some(U::from_u64(0)));
+    }
+
+    #[test]
+    fn convergents_satisfy_determinant_identity() {
+        // h_i k_(i-1) - h_(i-1) k_i = (-1)^(i+1)
+        let (a, b) = (U::from_u64(0xb7e151628aed2a6b), U::from_u64(0x243f6a8885a308d3));
+        let convergents: Vec<(i128, i128)> = Convergents::new(&a, &b)
+            .map(|(h, k)| (h.limbs[3] as i128, k.limbs[3] as i128))
+            .collect();
+        for (i, pair) in convergents.windows(2).enumerate() {
+            let ((h0, k0), (h1, k1)) = (pair[0], pair[1]);
+            assert_eq!(h1 * k0 - h0 * k1, if i % 2 == 0 { 1 } else { -1 });
+        }
+        assert_eq!(convergents.last(), Some(&(0xb7e151628aed2a6b, 0x243f6a8885a308d3)));
+    }
+
+    #[test]
+    fn wiener_attack_recovers_small_decryption_exponent() {
+        // full-width 256-bit n, with e d = 1 (mod phi(n)) and a 61-bit d
+        let n = U::from_hex_unchecked("d1e89e953a8e0a77b2234781c8a648de6a95ff1eccd7eea8ca5d167be1cfdbb5");
+        let e = U::from_hex_unchecked("16655a0fb06a38fd90bc319837855c78447672534e5befe76221851a042391fb");
+        assert_eq!(wiener_attack(&e, &n), Some(U::from_hex_unchecked("197482e07ad25f93")));
+
+        // d has 71 bits, beyond the bound
+        let e = U::from_hex_unchecked("a14715afc4681044ed8e847e546d825b84895790f5e2b4b1706745597eef2b5b");
+        assert_eq!(wiener_attack(&e, &n), None);
+    }
+}
@@ -0,0 +1,116 @@
+use lambdaworks_math::unsigned_integer::element::UnsignedInteger;
+
+use crate::{mod_inverse, mul_mod, xgcd};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrtError {
+    /// One of the moduli is zero.
+    ZeroModulus,
+    /// Two congruences have no common solution.
+    Inconsistent,
+    /// The lcm of the moduli does not fit in NUM_LIMBS limbs.
+    Overflow,
+}
+
+/// Solves the system x = residue_i (mod modulus_i) and returns (x, M),
+/// where M is the lcm of the moduli and x < M is the unique solution modulo M.
+/// Moduli need not be pairwise coprime, as long as the system is consistent.
+/// It is built on `xgcd` rather than `extended_euclidean_algorithm`, for the gcd and
+/// the signed Bezout coefficients (through `mod_inverse`) that non-coprime moduli need.
+pub fn crt<const NUM_LIMBS: usize>(
+        congruences: &[(UnsignedInteger<NUM_LIMBS>, UnsignedInteger<NUM_LIMBS>)],
+    ) -> Result<(UnsignedInteger<NUM_LIMBS>, UnsignedInteger<NUM_LIMBS>), CrtError> {
+    let zero = UnsignedInteger::<NUM_LIMBS>::from_u64(0);
+    let one = UnsignedInteger::<NUM_LIMBS>::from_u64(1);
+
+    let (mut x, mut m1) = (zero, one);
+    for (residue, m2) in congruences {
+        if *m2 == zero {
+            return Err(CrtError::ZeroModulus);
+        }
+        //
+        // Merge x = r1 (mod m1) with x = r2 (mod m2). With g = gcd(m1, m2),
+        // the system is solvable iff g divides r2 - r1, and then
+        //     x = r1 + m1 * k,   k = (r2 - r1) / g * (m1 / g)^(-1)  (mod m2 / g)
+        // is the solution modulo lcm(m1, m2) = m1 * (m2 / g).
+        //
+        let g = xgcd(m1, *m2).gcd;
+        let (_, r1) = x.div_rem(m2);
+        let (_, r2) = residue.div_rem(m2);
+        let diff = if r2 >= r1 { r2 - r1 } else { *m2 - r1 + r2 };
+        let (diff, rem) = diff.div_rem(&g);
+        if rem != zero {
+            return Err(CrtError::Inconsistent);
+        }
+
+        let (m1_reduced, _) = m1.div_rem(&g);
+        let (m2_reduced, _) = m2.div_rem(&g);
+        let (hi, lcm) = UnsignedInteger::mul(&m1, &m2_reduced);
+        if hi != zero {
+            return Err(CrtError::Overflow);
+        }
+
+        let inverse = mod_inverse(m1_reduced, &m2_reduced).unwrap();
+        let k = mul_mod(&diff, &inverse, &m2_reduced);
+        x = x + m1 * k;
+        m1 = lcm;
+    }
+    Ok((x, m1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type U = UnsignedInteger<2>;
+
+    fn system(congruences: &[(u64, u64)]) -> Vec<(U, U)> {
+        congruences.iter().map(|(r, m)| (U::from_u64(*r), U::from_u64(*m))).collect()
+    }
+
+    #[test]
+    fn crt_coprime_moduli() {
+        // Sunzi's problem: x = 2 (mod 3), x = 3 (mod 5), x = 2 (mod 7)
+        let solution = crt(&system(&[(2, 3), (3, 5), (2, 7)]));
+        assert_eq!(solution, Ok((U::from_u64(23), U::from_u64(105))));
+    }
+
+    #[test]
+    fn crt_non_coprime_moduli() {
+        let solution = crt(&system(&[(3, 4), (7, 10), (1, 3)]));
+        assert_eq!(solution, Ok((U::from_u64(7), U::from_u64(60))));
+        let solution = crt(&system(&[(3, 4), (6, 10)]));
+        assert_eq!(solution, Err(CrtError::Inconsistent));
+    }
+
+    #[test]
+    fn crt_errors() {
+        assert_eq!(crt(&system(&[(1, 3), (0, 0)])), Err(CrtError::ZeroModulus));
+        let primes = [(1, 0xffff_ffff_ffff_ffc5), (2, 0xffff_ffff_ffff_ff43), (3, 0xffff_ffff_ffff_fe95)];
+        assert_eq!(crt(&system(&primes)), Err(CrtError::Overflow));
+        assert_eq!(crt::<2>(&[]), Ok((U::from_u64(0), U::from_u64(1))));
+    }
+
+    #[test]
+    fn crt_matches_brute_force() {
+        for m1 in 1..10_u64 {
+            for m2 in 1..10_u64 {
+                for r1 in 0..m1 {
+                    for r2 in 0..m2 {
+                        let brute_force = (0..m1 * m2).find(|x| x % m1 == r1 && x % m2 == r2);
+                        match crt(&system(&[(r1, m1), (r2, m2)])) {
+                            Ok((x, m)) => {
+                                assert_eq!(Some(x.limbs[1]), brute_force);
+                                assert_eq!(m.limbs[1], m1 * m2 / xgcd(U::from_u64(m1), U::from_u64(m2)).gcd.limbs[1]);
+                            }
+                            Err(error) => {
+                                assert_eq!(error, CrtError::Inconsistent);
+                                assert_eq!(brute_force, None);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,400 @@
+//
+// Discrete logarithms: given g and h in a cyclic group, find x with g^x = h.
+// Every algorithm takes a budget (a bound on the exponent or on the number
+// of group operations) and returns None when it is exhausted.
+//
+use std::collections::{hash_map::DefaultHasher, HashMap};
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+use lambdaworks_math::cyclic_group::IsGroup;
+use lambdaworks_math::unsigned_integer::element::UnsignedInteger;
+use rand::prelude::*;
+
+use crate::{add_mod, crt, mod_inverse, mul_mod, sub_mod, ModularRing, Reducer};
+
+/// A group in which discrete logarithms are computed, written multiplicatively.
+pub trait DlogGroup<const NUM_LIMBS: usize> {
+    type Element: Clone + PartialEq;
+    /// Canonical encoding of an element: equal elements must have equal keys.
+    type Key: Hash + Eq;
+
+    fn identity(&self) -> Self::Element;
+    fn operate(&self, a: &Self::Element, b: &Self::Element) -> Self::Element;
+    fn inverse(&self, a: &Self::Element) -> Self::Element;
+    fn pow(&self, a: &Self::Element, exp: &UnsignedInteger<NUM_LIMBS>) -> Self::Element;
+    fn key(&self, a: &Self::Element) -> Self::Key;
+}
+
+/// Multiplicative group of integers modulo m, with elements in standard form.
+#[derive(Debug, Clone, Copy)]
+pub struct ModularGroup<const NUM_LIMBS: usize> {
+    ctx: Reducer<NUM_LIMBS>,
+}
+
+impl<const NUM_LIMBS: usize> ModularGroup<NUM_LIMBS> {
+    /// Returns `None` if the modulus is not supported by `Reducer`.
+    pub fn new(modulus: &UnsignedInteger<NUM_LIMBS>) -> Option<Self> {
+        Some(Self { ctx: Reducer::new(modulus)? })
+    }
+}
+
+impl<const NUM_LIMBS: usize> DlogGroup<NUM_LIMBS> for ModularGroup<NUM_LIMBS> {
+    type Element = UnsignedInteger<NUM_LIMBS>;
+    type Key = UnsignedInteger<NUM_LIMBS>;
+
+    fn identity(&self) -> Self::Element {
+        self.ctx.to_standard(&self.ctx.one())
+    }
+
+    fn operate(&self, a: &Self::Element, b: &Self::Element) -> Self::Element {
+        self.ctx.mul_mod(a, b)
+    }
+
+    /// Panics if `a` is not invertible.
+    fn inverse(&self, a: &Self::Element) -> Self::Element {
+        mod_inverse(*a, self.ctx.modulus()).expect("element is not invertible")
+    }
+
+    fn pow(&self, a: &Self::Element, exp: &UnsignedInteger<NUM_LIMBS>) -> Self::Element {
+        self.ctx.pow_mod(a, exp)
+    }
+
+    fn key(&self, a: &Self::Element) -> Self::Key {
+        *a
+    }
+}
+
+/// Any lambdaworks group, such as elliptic curve points, together with a function
+/// mapping its elements to a canonical key (e.g. the bytes of the affine coordinates).
+pub struct LambdaworksGroup<G, F> {
+    key: F,
+    _group: PhantomData<G>,
+}
+
+impl<G, F> LambdaworksGroup<G, F> {
+    pub fn new(key: F) -> Self {
+        Self { key, _group: PhantomData }
+    }
+}
+
+impl<G, K, F, const NUM_LIMBS: usize> DlogGroup<NUM_LIMBS> for LambdaworksGroup<G, F>
+where
+    G: IsGroup,
+    K: Hash + Eq,
+    F: Fn(&G) -> K,
+{
+    type Element = G;
+    type Key = K;
+
+    fn identity(&self) -> G {
+        G::neutral_element()
+    }
+
+    fn operate(&self, a: &G, b: &G) -> G {
+        a.operate_with(b)
+    }
+
+    fn inverse(&self, a: &G) -> G {
+        a.neg()
+    }
+
+    fn pow(&self, a: &G, exp: &UnsignedInteger<NUM_LIMBS>) -> G {
+        a.operate_with_self(*exp)
+    }
+
+    fn key(&self, a: &G) -> K {
+        (self.key)(a)
+    }
+}
+
+/// Baby-step giant-step: the smallest x < bound with g^x = h, using about
+/// 2 sqrt(bound) group operations and sqrt(bound) stored elements.
+pub fn baby_step_giant_step<const NUM_LIMBS: usize, G: DlogGroup<NUM_LIMBS>>(
+        group: &G,
+        g: &G::Element,
+        h: &G::Element,
+        bound: u64,
+    ) -> Option<UnsignedInteger<NUM_LIMBS>> {
+    let mut m = (bound as f64).sqrt() as u64;
+    while (m as u128) * (m as u128) < bound as u128 {
+        m += 1;
+    }
+
+    // baby steps: g^j -> j for 0 <= j < m
+    let mut table = HashMap::with_capacity(m as usize);
+    let mut baby = group.identity();
+    for j in 0..m {
+        table.entry(group.key(&baby)).or_insert(j);
+        baby = group.operate(&baby, g);
+    }
+
+    // giant steps: h g^(-i m) for 0 <= i < m
+    let giant_step = group.inverse(&baby);
+    let mut giant = h.clone();
+    for i in 0..m {
+        if let Some(j) = table.get(&group.key(&giant)) {
+            let x = i as u128 * m as u128 + *j as u128;
+            return (x < bound as u128).then(|| UnsignedInteger::from_u64(x as u64));
+        }
+        giant = group.operate(&giant, &giant_step);
+    }
+    None
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CycleDetection {
+    Floyd,
+    Brent,
+}
+
+/// Point of Pollard's walk: x = g^a h^b.
+#[derive(Clone)]
+struct WalkPoint<E, const NUM_LIMBS: usize> {
+    x: E,
+    a: UnsignedInteger<NUM_LIMBS>,
+    b: UnsignedInteger<NUM_LIMBS>,
+}
+
+fn random_below<const NUM_LIMBS: usize>(n: &UnsignedInteger<NUM_LIMBS>) -> UnsignedInteger<NUM_LIMBS> {
+    let mut limbs = [0_u64; NUM_LIMBS];
+    rand::thread_rng().fill(&mut limbs[..]);
+    UnsignedInteger::from_limbs(limbs).div_rem(n).1
+}
+
+/// Pollard's rho for g of prime order `order`, with at most `max_steps` group
+/// operations in total. The walk multiplies by g, squares, or multiplies by h
+/// according to the hash of the current element, and restarts from a random
+/// point when a collision gives no information.
+pub fn pollard_rho<const NUM_LIMBS: usize, G: DlogGroup<NUM_LIMBS>>(
+        group: &G,
+        g: &G::Element,
+        h: &G::Element,
+        order: &UnsignedInteger<NUM_LIMBS>,
+        cycle_detection: CycleDetection,
+        max_steps: u64,
+    ) -> Option<UnsignedInteger<NUM_LIMBS>> {
+    let one = UnsignedInteger::<NUM_LIMBS>::from_u64(1);
+    let zero = UnsignedInteger::<NUM_LIMBS>::from_u64(0);
+
+    let partition = |x: &G::Element| {
+        let mut hasher = DefaultHasher::new();
+        group.key(x).hash(&mut hasher);
+        hasher.finish() % 3
+    };
+    let step = |p: &WalkPoint<G::Element, NUM_LIMBS>| match partition(&p.x) {
+        0 => WalkPoint { x: group.operate(&p.x, g), a: add_mod(&p.a, &one, order), b: p.b },
+        1 => WalkPoint {
+            x: group.operate(&p.x, &p.x),
+            a: add_mod(&p.a, &p.a, order),
+            b: add_mod(&p.b, &p.b, order),
+        },
+        _ => WalkPoint { x: group.operate(&p.x, h), a: p.a, b: add_mod(&p.b, &one, order) },
+    };
+
+    let mut steps = 0;
+    while steps < max_steps {
+        let (a, b) = (random_below(order), random_below(order));
+        let start = WalkPoint { x: group.operate(&group.pow(g, &a), &group.pow(h, &b)), a, b };
+
+        let (mut tortoise, mut hare) = (start.clone(), step(&start));
+        steps += 1;
+        match cycle_detection {
+            CycleDetection::Floyd => {
+                while tortoise.x != hare.x && steps < max_steps {
+                    tortoise = step(&tortoise);
+                    hare = step(&step(&hare));
+                    steps += 3;
+                }
+            }
+            CycleDetection::Brent => {
+                let (mut power, mut lambda) = (1_u64, 1_u64);
+                while tortoise.x != hare.x && steps < max_steps {
+                    if power == lambda {
+                        tortoise = hare.clone();
+                        power *= 2;
+                        lambda = 0;
+                    }
+                    hare = step(&hare);
+                    lambda += 1;
+                    steps += 1;
+                }
+            }
+        }
+        if tortoise.x != hare.x {
+            break;
+        }
+
+        // g^a1 h^b1 = g^a2 h^b2, so x (b2 - b1) = a1 - a2 (mod order)
+        let db = sub_mod(&hare.b, &tortoise.b, order);
+        if db == zero {
+            continue;
+        }
+        let Some(db_inverse) = mod_inverse(db, order) else {
+            continue;
+        };
+        let x = mul_mod(&sub_mod(&tortoise.a, &hare.a, order), &db_inverse, order);
+        if group.pow(g, &x) == *h {
+            return Some(x);
+        }
+    }
+    None
+}
+
+/// Discrete logarithm in a subgroup of prime order q: baby-step giant-step when
+/// sqrt(q) fits in the budget, and Pollard's rho otherwise.
+fn prime_order_dlog<const NUM_LIMBS: usize, G: DlogGroup<NUM_LIMBS>>(
+        group: &G,
+        g: &G::Element,
+        h: &G::Element,
+        q: &UnsignedInteger<NUM_LIMBS>,
+        max_steps: u64,
+    ) -> Option<UnsignedInteger<NUM_LIMBS>> {
+    if q.bits_le() <= 64 && (q.limbs[NUM_LIMBS - 1] as u128) <= (max_steps as u128).pow(2) {
+        baby_step_giant_step(group, g, h, q.limbs[NUM_LIMBS - 1])
+    } else {
+        pollard_rho(group, g, h, q, CycleDetection::Brent, max_steps)
+    }
+}
+
+/// Pohlig-Hellman: discrete logarithm for g whose order divides
+/// n = prod q_i^e_i, given as (q_i, e_i) pairs. It reduces to e_i logarithms
+/// in subgroups of order q_i, each solved within `max_steps` group operations,
+/// and recombines them with the Chinese Remainder Theorem.
+pub fn pohlig_hellman<const NUM_LIMBS: usize, G: DlogGroup<NUM_LIMBS>>(
+        group: &G,
+        g: &G::Element,
+        h: &G::Element,
+        factorization: &[(UnsignedInteger<NUM_LIMBS>, u32)],
+        max_steps: u64,
+    ) -> Option<UnsignedInteger<NUM_LIMBS>> {
+    let zero = UnsignedInteger::<NUM_LIMBS>::from_u64(0);
+    let one = UnsignedInteger::<NUM_LIMBS>::from_u64(1);
+    let pow_u32 = |q: &UnsignedInteger<NUM_LIMBS>, e: u32| (0..e).fold(one, |acc, _| acc * q);
+
+    // q^0 = 1 contributes nothing, and n must not overflow
+    let factorization: Vec<_> = factorization.iter().filter(|(_, e)| *e > 0).collect();
+    let mut n = one;
+    for (q, e) in &factorization {
+        for _ in 0..*e {
+            let (hi, lo) = UnsignedInteger::mul(&n, q);
+            if hi != zero {
+                return None;
+            }
+            n = lo;
+        }
+    }
+    let mut congruences = Vec::with_capacity(factorization.len());
+    for (q, e) in factorization {
+        let q_e = pow_u32(q, *e);
+        let (cofactor, _) = n.div_rem(&q_e);
+        // g_i has order dividing q^e, and gamma order dividing q.
+        let g_i = group.pow(g, &cofactor);
+        let h_i = group.pow(h, &cofactor);
+        let gamma = group.pow(&g_i, &pow_u32(q, e - 1));
+
+        // x = x_0 + x_1 q + ... + x_(e - 1) q^(e - 1), one digit at a time:
+        // (g_i^(-x) h_i)^(q^(e - 1 - k)) = gamma^(x_k)
+        let mut x = UnsignedInteger::<NUM_LIMBS>::from_u64(0);
+        for k in 0..*e {
+            let t = group.operate(&group.inverse(&group.pow(&g_i, &x)), &h_i);
+            let h_k = group.pow(&t, &pow_u32(q, e - 1 - k));
+            let digit = prime_order_dlog(group, &gamma, &h_k, q, max_steps)?;
+            x = x + digit * pow_u32(q, k);
+        }
+        congruences.push((x, q_e));
+    }
+
+    let (x, _) = crt(&congruences).ok()?;
+    (group.pow(g, &x) == *h).then_some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lambdaworks_math::elliptic_curve::{
+        short_weierstrass::curves::bls12_381::curve::BLS12381Curve,
+        traits::IsEllipticCurve,
+    };
+    use lambdaworks_math::traits::AsBytes;
+
+    type U = UnsignedInteger<4>;
+
+    #[test]
+    fn bsgs_modular_group() {
+        let p = U::from_u64(1_000_003);
+        let group = ModularGroup::new(&p).unwrap();
+        // 2 is a primitive root modulo 1000003
+        let g = U::from_u64(2);
+        for x in [0_u64, 1, 2, 999, 1000, 123_456, 1_000_001] {
+            let h = group.pow(&g, &U::from_u64(x));
+            assert_eq!(baby_step_giant_step(&group, &g, &h, 1_000_002), Some(U::from_u64(x)));
+        }
+        // budget too small
+        let h = group.pow(&g, &U::from_u64(5000));
+        assert_eq!(baby_step_giant_step(&group, &g, &h, 4000), None);
+    }
+
+    #[test]
+    fn bsgs_even_modulus() {
+        // 3 has order 2^18 modulo 2^20
+        let group = ModularGroup::new(&U::from_u64(1 << 20)).unwrap();
+        let g = U::from_u64(3);
+        let h = group.pow(&g, &U::from_u64(200_001));
+        assert_eq!(group.operate(&h, &group.inverse(&h)), group.identity());
+        assert_eq!(baby_step_giant_step(&group, &g, &h, 1 << 18), Some(U::from_u64(200_001)));
+    }
+
+    #[test]
+    fn pollard_rho_prime_order_subgroup() {
+        // p = 2q + 1 with q prime, and 4 generates the subgroup of order q
+        let (p, q) = (U::from_u64(2147483783), U::from_u64(1073741891));
+        let group = ModularGroup::new(&p).unwrap();
+        let g = U::from_u64(4);
+        let x = U::from_u64(987_654_321);
+        let h = group.pow(&g, &x);
+        for cycle_detection in [CycleDetection::Floyd, CycleDetection::Brent] {
+            assert_eq!(pollard_rho(&group, &g, &h, &q, cycle_detection, 1_000_000), Some(x));
+        }
+        assert_eq!(pollard_rho(&group, &g, &h, &q, CycleDetection::Brent, 10), None);
+    }
+
+    #[test]
+    fn pohlig_hellman_bls12_381_scalar_field() {
+        // r - 1 is smooth enough to solve full-size discrete logarithms modulo r.
+        let r = U::from_hex_unchecked("73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001");
+        let factorization: Vec<(U, u32)> = [
+            (2, 32), (3, 1), (11, 1), (19, 1), (10177, 1), (125527, 1), (859267, 1), (906349, 2),
+            (2508409, 1), (2529403, 1), (52437899, 1), (254760293, 2),
+        ].iter().map(|(p, e)| (U::from_u64(*p), *e)).collect();
+        let group = ModularGroup::new(&r).unwrap();
+        let g = U::from_u64(7);
+        let x = U::from_hex_unchecked("1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef");
+        let h = group.pow(&g, &x);
+        assert_eq!(pohlig_hellman(&group, &g, &h, &factorization, 1 << 16), Some(x));
+    }
+
+    #[test]
+    fn pohlig_hellman_degenerate_factorizations() {
+        // 2 is a primitive root modulo 1000003, and 1000002 = 2 * 3 * 166667
+        let p = U::from_u64(1_000_003);
+        let group = ModularGroup::new(&p).unwrap();
+        let (g, x) = (U::from_u64(2), U::from_u64(765_432));
+        let h = group.pow(&g, &x);
+        let factorization = [(U::from_u64(2), 1), (U::from_u64(5), 0), (U::from_u64(3), 1), (U::from_u64(166_667), 1)];
+        assert_eq!(pohlig_hellman(&group, &g, &h, &factorization, 1 << 10), Some(x));
+        // 2^300 does not fit in 4 limbs
+        assert_eq!(pohlig_hellman(&group, &g, &h, &[(U::from_u64(2), 300)], 1 << 10), None);
+    }
+
+    #[test]
+    fn bsgs_elliptic_curve_group() {
+        let group = LambdaworksGroup::new(|p: &<BLS12381Curve as IsEllipticCurve>::PointRepresentation| {
+            p.to_affine().as_bytes()
+        });
+        let g = BLS12381Curve::generator();
+        let h = g.operate_with_self(12345_u64);
+        assert_eq!(baby_step_giant_step::<4, _>(&group, &g, &h, 1 << 16), Some(U::from_u64(12345)));
+        assert_eq!(baby_step_giant_step::<4, _>(&group, &g, &h, 10000), None);
+    }
+}
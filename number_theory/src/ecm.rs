@@ -0,0 +1,339 @@
+//
+// Lenstra's elliptic curve method. Each curve is a Montgomery curve
+// B y^2 = x^3 + A x^2 + x modulo n, chosen with Suyama's parametrization so that
+// its order is divisible by 12, and handled in projective (X : Z) coordinates.
+// Stage 1 multiplies a point by every prime power up to B1. Stage 2 looks for
+// a single extra prime q in (B1, B2] with the baby-step giant-step
+// continuation: x([m D] Q) = x([j] Q) modulo p whenever m D ± j = q kills Q.
+//
+use lambdaworks_math::unsigned_integer::element::UnsignedInteger;
+use rand::prelude::*;
+
+use crate::{add_mod, gcd, mod_inverse, sub_mod, MontgomeryContext, PrimeIterator};
+
+/// Giant step of stage 2. Baby steps are the j < D / 2 coprime to D.
+const STAGE_2_GIANT_STEP: u64 = 210;
+
+/// Bounds and number of curves of an ECM run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EcmParams {
+    /// Stage 1 bound. Bounds below D / 2 = 105 are raised to 105, the smallest
+    /// for which every prime of stage 2 pairs with a baby step.
+    pub b1: u64,
+    /// Stage 2 bound. Stage 2 is skipped when `b2 <= b1`.
+    pub b2: u64,
+    /// Number of curves to try.
+    pub curves: usize,
+}
+
+impl EcmParams {
+    /// Parameters that usually find a prime factor of up to `digits` decimal digits,
+    /// following the classic table of Silverman and Wagstaff with B2 = 100 B1.
+    pub fn for_digits(digits: u32) -> Self {
+        let (b1, curves) = match digits {
+            0..=15 => (2_000, 25),
+            16..=20 => (11_000, 90),
+            21..=25 => (50_000, 300),
+            26..=30 => (250_000, 700),
+            _ => (1_000_000, 1_800),
+        };
+        Self { b1, b2: 100 * b1, curves }
+    }
+}
+
+/// A point (X : Z) of a Montgomery curve, in Montgomery form. Z = 0 is the point at infinity.
+#[derive(Debug, Clone, Copy)]
+struct Point<const NUM_LIMBS: usize> {
+    x: UnsignedInteger<NUM_LIMBS>,
+    z: UnsignedInteger<NUM_LIMBS>,
+}
+
+/// The curve arithmetic, which only needs (A + 2) / 4 besides the modulus.
+struct Curve<'a, const NUM_LIMBS: usize> {
+    ctx: &'a MontgomeryContext<NUM_LIMBS>,
+    a24: UnsignedInteger<NUM_LIMBS>,
+}
+
+impl<const NUM_LIMBS: usize> Curve<'_, NUM_LIMBS> {
+    fn double(&self, p: &Point<NUM_LIMBS>) -> Point<NUM_LIMBS> {
+        let n = self.ctx.modulus();
+        let sum = self.ctx.square(&add_mod(&p.x, &p.z, n));
+        let difference = self.ctx.square(&sub_mod(&p.x, &p.z, n));
+        // 4 X Z = (X + Z)^2 - (X - Z)^2
+        let t = sub_mod(&sum, &difference, n);
+        Point {
+            x: self.ctx.mul(&sum, &difference),
+            z: self.ctx.mul(&t, &add_mod(&difference, &self.ctx.mul(&self.a24, &t), n)),
+        }
+    }
+
+    /// P + Q, given P - Q.
+    fn add(&self, p: &Point<NUM_LIMBS>, q: &Point<NUM_LIMBS>, difference: &Point<NUM_LIMBS>) -> Point<NUM_LIMBS> {
+        let n = self.ctx.modulus();
+        let u = self.ctx.mul(&sub_mod(&p.x, &p.z, n), &add_mod(&q.x, &q.z, n));
+        let v = self.ctx.mul(&add_mod(&p.x, &p.z, n), &sub_mod(&q.x, &q.z, n));
+        Point {
+            x: self.ctx.mul(&difference.z, &self.ctx.square(&add_mod(&u, &v, n))),
+            z: self.ctx.mul(&difference.x, &self.ctx.square(&sub_mod(&u, &v, n))),
+        }
+    }
+
+    /// [k] P for k > 0, by the Montgomery ladder.
+    fn scalar_mul(&self, p: &Point<NUM_LIMBS>, k: u64) -> Point<NUM_LIMBS> {
+        // invariant: r1 - r0 = P
+        let (mut r0, mut r1) = (*p, self.double(p));
+        for i in (0..63 - k.leading_zeros()).rev() {
+            if (k >> i) & 1 == 1 {
+                r0 = self.add(&r1, &r0, p);
+                r1 = self.double(&r1);
+            } else {
+                r1 = self.add(&r1, &r0, p);
+                r0 = self.double(&r0);
+            }
+        }
+        r0
+    }
+}
+
+/// Outcome of a gcd with n: a factor, nothing yet, or everything at once.
+enum Split<const NUM_LIMBS: usize> {
+    Factor(UnsignedInteger<NUM_LIMBS>),
+    Coprime,
+    Degenerate,
+}
+
+fn split<const NUM_LIMBS: usize>(a: &UnsignedInteger<NUM_LIMBS>, n: &UnsignedInteger<NUM_LIMBS>) -> Split<NUM_LIMBS> {
+    let g = gcd(a, n);
+    if g == UnsignedInteger::from_u64(1) {
+        Split::Coprime
+    } else if g == *n {
+        Split::Degenerate
+    } else {
+        Split::Factor(g)
+    }
+}
+
+/// Primes of stages 1 and 2 for the given bounds, shared by all the curves.
+struct Plan {
+    /// Largest powers of the primes up to B1 not exceeding B1.
+    prime_powers: Vec<u64>,
+    /// Baby steps j < D / 2 coprime to D.
+    baby_steps: Vec<u64>,
+    /// First giant step m.
+    first_giant_step: u64,
+    /// For each giant step m, bit i is set when m D ± baby_steps[i] is a prime in (B1, B2].
+    pairs: Vec<u64>,
+}
+
+impl Plan {
+    /// Panics if B1 < D / 2, since primes up to D / 2 would have no giant step.
+    fn new(b1: u64, b2: u64) -> Self {
+        let d = STAGE_2_GIANT_STEP;
+        assert!(b1 >= d / 2, "stage 1 bound below half the giant step");
+        let mut prime_powers = vec![];
+        for p in PrimeIterator::up_to(b1) {
+            let mut prime_power = p;
+            while prime_power <= b1 / p {
+                prime_power *= p;
+            }
+            prime_powers.push(prime_power);
+        }
+
+        let baby_steps: Vec<u64> = (1..d / 2).filter(|j| [2, 3, 5, 7].iter().all(|p| j % p != 0)).collect();
+        // every q > B1 is m D ± j with j <= D / 2 and m = round(q / D) >= B1 / D
+        let first_giant_step = (b1 / d).max(1);
+        let mut pairs = vec![];
+        if b2 > b1 {
+            pairs = vec![0; (b2 / d + 2 - first_giant_step) as usize];
+            for q in PrimeIterator::between(b1 + 1, b2) {
+                let m = (q + d / 2) / d;
+                let j = q.abs_diff(m * d);
+                if let Ok(i) = baby_steps.binary_search(&j) {
+                    pairs[(m - first_giant_step) as usize] |= 1 << i;
+                }
+            }
+        }
+        Self { prime_powers, baby_steps, first_giant_step, pairs }
+    }
+}
+
+/// Runs both stages on the curve with Suyama parameter `sigma`.
+/// Returns a nontrivial factor of n.
+fn ecm_curve<const NUM_LIMBS: usize>(
+        ctx: &MontgomeryContext<NUM_LIMBS>,
+        sigma: u64,
+        plan: &Plan,
+    ) -> Option<UnsignedInteger<NUM_LIMBS>> {
+    let n = ctx.modulus();
+    let zero = UnsignedInteger::<NUM_LIMBS>::from_u64(0);
+    let small = |k: u64| ctx.to_mont(&UnsignedInteger::from_u64(k));
+
+    // u = sigma^2 - 5, v = 4 sigma, starting point (u^3 : v^3) and
+    // (A + 2) / 4 = (v - u)^3 (3 u + v) / (16 u^3 v).
+    let sigma = small(sigma);
+    let u = sub_mod(&ctx.square(&sigma), &small(5), n);
+    let v = ctx.mul(&small(4), &sigma);
+    let u3 = ctx.mul(&ctx.square(&u), &u);
+    let v3 = ctx.mul(&ctx.square(&v), &v);
+    let v_minus_u = sub_mod(&v, &u, n);
+    let numerator = ctx.mul(
+        &ctx.mul(&ctx.square(&v_minus_u), &v_minus_u),
+        &add_mod(&ctx.mul(&small(3), &u), &v, n),
+    );
+    let denominator = ctx.from_mont(&ctx.mul(&ctx.mul(&small(16), &u3), &v));
+    // a failed inversion may already split n
+    match split(&denominator, n) {
+        Split::Factor(g) => return Some(g),
+        Split::Degenerate => return None,
+        Split::Coprime => {}
+    }
+    let inverse = ctx.to_mont(&mod_inverse(denominator, n)?);
+    let curve = Curve { ctx, a24: ctx.mul(&numerator, &inverse) };
+    if curve.a24 == zero {
+        // A = -2, a singular curve
+        return None;
+    }
+
+    // Stage 1: Q = [k] P with k the product of the prime powers up to B1.
+    let mut q = Point { x: u3, z: v3 };
+    for &prime_power in &plan.prime_powers {
+        q = curve.scalar_mul(&q, prime_power);
+    }
+    match split(&q.z, n) {
+        Split::Factor(g) => return Some(g),
+        Split::Degenerate => return None,
+        Split::Coprime => {}
+    }
+    if plan.pairs.is_empty() {
+        return None;
+    }
+
+    // Stage 2: baby steps [j] Q for odd j, by [j + 2] Q = [j] Q + [2] Q.
+    let d = STAGE_2_GIANT_STEP;
+    let q2 = curve.double(&q);
+    let mut odd_multiples = vec![q, curve.add(&q2, &q, &q)];
+    while (odd_multiples.len() as u64) < d / 4 {
+        let i = odd_multiples.len();
+        odd_multiples.push(curve.add(&odd_multiples[i - 1], &q2, &odd_multiples[i - 2]));
+    }
+    let baby_steps: Vec<_> = plan.baby_steps.iter().map(|j| odd_multiples[(j / 2) as usize]).collect();
+
+    // Giant steps T_m = [m D] Q, by T_m+1 = T_m + [D] Q.
+    let qd = curve.scalar_mul(&q, d);
+    let m = plan.first_giant_step;
+    let mut giant = curve.scalar_mul(&q, m * d);
+    let mut previous = if m == 1 { giant } else { curve.scalar_mul(&q, (m - 1) * d) };
+    let mut product = ctx.one();
+    for (step, &mask) in plan.pairs.iter().enumerate() {
+        for (i, baby) in baby_steps.iter().enumerate() {
+            if (mask >> i) & 1 == 1 {
+                // X_T Z_j - X_j Z_T vanishes modulo p when x(T) = x([j] Q)
+                let difference = sub_mod(&ctx.mul(&giant.x, &baby.z), &ctx.mul(&baby.x, &giant.z), n);
+                product = ctx.mul(&product, &difference);
+            }
+        }
+        if step + 1 < plan.pairs.len() {
+            // T_0 is the point at infinity, which the differential addition cannot take
+            let next = if step == 0 && m == 1 {
+                curve.double(&giant)
+            } else {
+                curve.add(&giant, &qd, &previous)
+            };
+            previous = giant;
+            giant = next;
+        }
+    }
+    match split(&product, n) {
+        Split::Factor(g) => Some(g),
+        _ => None,
+    }
+}
+
+/// Lenstra's elliptic curve method for odd n > 1 that is not a prime power:
+/// tries `params.curves` random curves and returns a nontrivial factor of n.
+/// A prime factor p is found when some curve has a group order modulo p that is
+/// B1-smooth except for at most one prime up to B2.
+pub fn ecm<const NUM_LIMBS: usize>(
+        n: &UnsignedInteger<NUM_LIMBS>,
+        params: &EcmParams,
+    ) -> Option<UnsignedInteger<NUM_LIMBS>> {
+    let ctx = MontgomeryContext::new(n)?;
+    let plan = Plan::new(params.b1.max(STAGE_2_GIANT_STEP / 2), params.b2);
+    let mut rng = rand::thread_rng();
+    (0..params.curves).find_map(|_| ecm_curve(&ctx, rng.gen_range(6..1 << 32), &plan))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::is_prime_bpsw;
+
+    #[test]
+    fn ladder_matches_repeated_addition() {
+        let n = UnsignedInteger::<2>::from_u128(0xffff_ffff_ffff_ffc5 * 0xffff_ffff_ffff_ff43);
+        let ctx = MontgomeryContext::new(&n).unwrap();
+        let curve = Curve { ctx: &ctx, a24: ctx.to_mont(&UnsignedInteger::from_u64(12345)) };
+        let p = Point { x: ctx.to_mont(&UnsignedInteger::from_u64(7)), z: ctx.one() };
+        // [k + 1] P = [k] P + P, with difference [k - 1] P
+        let (mut previous, mut current) = (p, curve.double(&p));
+        for k in 2..200 {
+            let expected = curve.scalar_mul(&p, k);
+            // same projective point
+            assert_eq!(ctx.mul(&current.x, &expected.z), ctx.mul(&expected.x, &current.z));
+            (previous, current) = (current, curve.add(&current, &p, &previous));
+        }
+    }
+
+    #[test]
+    fn stage_2_covers_every_prime() {
+        // every prime q in (105, 10000] is m D ± j for a marked pair (m, j)
+        let d = STAGE_2_GIANT_STEP;
+        let plan = Plan::new(d / 2, 10_000);
+        for q in PrimeIterator::between(d / 2 + 1, 10_000) {
+            let m = (q + d / 2) / d;
+            let i = plan.baby_steps.binary_search(&q.abs_diff(m * d)).unwrap();
+            assert_eq!((plan.pairs[(m - plan.first_giant_step) as usize] >> i) & 1, 1, "q = {q}");
+        }
+        // small bounds are raised rather than leaving primes out of both stages
+        let n = UnsignedInteger::<2>::from_hex_unchecked("52e63a91ba19436b453af5f01adfe305");
+        assert!(ecm(&n, &EcmParams { b1: 10, b2: 50_000, curves: 200 }).is_some());
+    }
+
+    #[test]
+    fn stage_2_finds_more_factors() {
+        // 693827181407 * 158817807676264262589120539
+        let n = UnsignedInteger::<2>::from_hex_unchecked("52e63a91ba19436b453af5f01adfe305");
+        let ctx = MontgomeryContext::new(&n).unwrap();
+        let stage_1 = Plan::new(500, 500);
+        let stage_2 = Plan::new(500, 50_000);
+        let (mut found_1, mut found_2) = (0, 0);
+        for sigma in 6..86 {
+            if let Some(g) = ecm_curve(&ctx, sigma, &stage_1) {
+                assert_eq!(g, UnsignedInteger::from_u64(693827181407));
+                found_1 += 1;
+            }
+            if let Some(g) = ecm_curve(&ctx, sigma, &stage_2) {
+                assert_eq!(g, UnsignedInteger::from_u64(693827181407));
+                found_2 += 1;
+            }
+        }
+        assert!(found_2 > found_1);
+    }
+
+    #[test]
+    fn ecm_splits_unbalanced_semiprime() {
+        let n = UnsignedInteger::<2>::from_hex_unchecked("52e63a91ba19436b453af5f01adfe305");
+        let g = ecm(&n, &EcmParams::for_digits(15)).unwrap();
+        assert_eq!(g, UnsignedInteger::from_u64(693827181407));
+    }
+
+    #[test]
+    #[ignore = "slow without optimizations, run with --release"]
+    fn ecm_splits_128_bit_rsa_modulus() {
+        // 14007609107148676207 * 13546015361446459657
+        let n = UnsignedInteger::<2>::from_hex_unchecked("8ebfff97cc519f853bd39d6d65f136e7");
+        let g = ecm(&n, &EcmParams::for_digits(20)).unwrap();
+        assert!(is_prime_bpsw(&g));
+        assert_eq!(n.div_rem(&g).1, UnsignedInteger::from_u64(0));
+    }
+}
@@ -0,0 +1,311 @@
+//
+// Integer factorization. `factor` strips small primes by trial division and
+// then splits the remaining composites with Fermat's method, Pollard's p - 1,
+// Pollard's rho, the elliptic curve method and the quadratic sieve, checking
+// every cofactor with the BPSW primality test.
+//
+use lambdaworks_math::unsigned_integer::element::UnsignedInteger;
+
+use crate::{
+    add_mod, div_u64,
+    ecm::{ecm, EcmParams},
+    gcd, is_prime_bpsw, isqrt, rem_u64,
+    siqs::{siqs, SiqsParams},
+    sub_mod, MontgomeryContext, PrimeIterator,
+};
+
+/// Primes below this bound are removed by trial division in `factor`.
+const TRIAL_DIVISION_BOUND: u64 = 1 << 12;
+
+/// Smoothness bound of Pollard's p - 1 in `factor`.
+const P_MINUS_1_BOUND: u64 = 1 << 14;
+
+/// Number of iterations of Fermat's method in `factor`.
+const FERMAT_STEPS: u64 = 1 << 10;
+
+/// Number of iterations of Pollard's rho in `factor` before switching to ECM.
+const RHO_STEPS: u64 = 1 << 18;
+
+/// Factor sizes, in decimal digits, targeted by the ECM runs in `factor` before the quadratic sieve.
+const ECM_DIGITS: [u32; 2] = [15, 20];
+
+/// Factor sizes targeted by the ECM runs in `factor` when the quadratic sieve fails.
+const LATE_ECM_DIGITS: [u32; 3] = [25, 30, 35];
+
+/// Number of products accumulated between two gcds in Pollard's rho.
+const RHO_BATCH_SIZE: u64 = 128;
+
+/// Removes the prime factors below `bound` from `n`. Returns them as
+/// (prime, exponent) pairs, together with the remaining cofactor.
+pub fn trial_division<const NUM_LIMBS: usize>(
+        n: &UnsignedInteger<NUM_LIMBS>,
+        bound: u64,
+    ) -> (Vec<(UnsignedInteger<NUM_LIMBS>, u32)>, UnsignedInteger<NUM_LIMBS>) {
+    let mut factors = vec![];
+    let mut cofactor = *n;
+    for p in PrimeIterator::up_to(bound.saturating_sub(1)) {
+        if cofactor.bits_le() <= 64 && (cofactor.limbs[NUM_LIMBS - 1] as u128) < (p as u128) * (p as u128) {
+            break;
+        }
+        let mut exponent = 0;
+        while rem_u64(&cofactor, p) == 0 {
+            (cofactor, _) = div_u64(&cofactor, p);
+            exponent += 1;
+        }
+        if exponent > 0 {
+            factors.push((UnsignedInteger::from_u64(p), exponent));
+        }
+    }
+    (factors, cofactor)
+}
+
+/// Fermat's method for odd n: searches n = a^2 - b^2 = (a - b)(a + b) for at most
+/// `max_steps` values of a from sqrt(n) up. Fast when n has two close factors.
+/// Returns a nontrivial factor of n.
+pub fn fermat<const NUM_LIMBS: usize>(
+        n: &UnsignedInteger<NUM_LIMBS>,
+        max_steps: u64,
+    ) -> Option<UnsignedInteger<NUM_LIMBS>> {
+    let zero = UnsignedInteger::<NUM_LIMBS>::from_u64(0);
+    let one = UnsignedInteger::<NUM_LIMBS>::from_u64(1);
+
+    let mut a = isqrt(n);
+    if a * a == *n {
+        return (a != one).then_some(a);
+    }
+    a = a + one;
+    let (hi, a_squared) = UnsignedInteger::mul(&a, &a);
+    if hi != zero {
+        return None;
+    }
+    // b2 = a^2 - n, updated with (a + 1)^2 - a^2 = 2a + 1
+    let mut b2 = a_squared - n;
+    for _ in 0..max_steps {
+        let b = isqrt(&b2);
+        if b * b == b2 {
+            let factor = a - b;
+            return (factor != one).then_some(factor);
+        }
+        let (next, carry) = UnsignedInteger::add(&b2, &((a << 1) + one));
+        if carry {
+            return None;
+        }
+        b2 = next;
+        a = a + one;
+    }
+    None
+}
+
+/// Pollard's rho with Brent's cycle detection for odd composite n: iterates
+/// y -> y^2 + c modulo n and looks for gcd(x - y, n) > 1, batching the gcds.
+/// Gives up after `max_steps` iterations in total. Returns a nontrivial factor of n.
+pub fn pollard_rho<const NUM_LIMBS: usize>(
+        n: &UnsignedInteger<NUM_LIMBS>,
+        max_steps: u64,
+    ) -> Option<UnsignedInteger<NUM_LIMBS>> {
+    let one = UnsignedInteger::<NUM_LIMBS>::from_u64(1);
+    let ctx = MontgomeryContext::new(n)?;
+    //
+    // Values are kept in Montgomery form: x R - y R = (x - y) R and R is
+    // coprime to n, so the gcds are not affected.
+    //
+    let mut steps = 0;
+    let mut c = 1;
+    while steps < max_steps {
+        let c_mont = ctx.to_mont(&UnsignedInteger::from_u64(c));
+        let f = |y: &UnsignedInteger<NUM_LIMBS>| add_mod(&ctx.square(y), &c_mont, n);
+
+        let (mut x, mut y, mut ys) = (ctx.one(), ctx.to_mont(&UnsignedInteger::from_u64(2)), ctx.one());
+        let mut product = ctx.one();
+        let mut g = one;
+        let mut r = 1;
+        while g == one && steps < max_steps {
+            x = y;
+            for _ in 0..r {
+                y = f(&y);
+            }
+            let mut k = 0;
+            while k < r && g == one {
+                ys = y;
+                for _ in 0..RHO_BATCH_SIZE.min(r - k) {
+                    y = f(&y);
+                    product = ctx.mul(&product, &sub_mod(&x, &y, n));
+                }
+                g = gcd(&product, n);
+                k += RHO_BATCH_SIZE;
+            }
+            steps += 2 * r;
+            r *= 2;
+        }
+        if g == *n {
+            // the batch overshot: redo it one gcd at a time
+            loop {
+                ys = f(&ys);
+                g = gcd(&sub_mod(&x, &ys, n), n);
+                if g != one {
+                    break;
+                }
+            }
+        }
+        if g != one && g != *n {
+            return Some(g);
+        }
+        c += 1;
+    }
+    None
+}
+
+/// Pollard's p - 1: finds a prime factor p of odd n when p - 1 is `bound`-smooth,
+/// that is, a product of prime powers not larger than `bound`.
+/// Returns a nontrivial factor of n.
+pub fn pollard_p_minus_1<const NUM_LIMBS: usize>(
+        n: &UnsignedInteger<NUM_LIMBS>,
+        bound: u64,
+    ) -> Option<UnsignedInteger<NUM_LIMBS>> {
+    let one = UnsignedInteger::<NUM_LIMBS>::from_u64(1);
+    let ctx = MontgomeryContext::new(n)?;
+
+    // a = 2^M, where M is the product of the largest powers of the primes below bound.
+    let mut a = ctx.to_mont(&UnsignedInteger::from_u64(2));
+    for (i, p) in PrimeIterator::up_to(bound).enumerate() {
+        let mut prime_power = p;
+        while prime_power <= bound / p {
+            prime_power *= p;
+        }
+        a = ctx.pow(&a, &UnsignedInteger::from_u64(prime_power));
+        if i % 32 == 31 {
+            // check from time to time, before every prime of n divides a - 1
+            let g = gcd(&sub_mod(&a, &ctx.one(), n), n);
+            if g != one {
+                return (g != *n).then_some(g);
+            }
+        }
+    }
+    let g = gcd(&sub_mod(&a, &ctx.one(), n), n);
+    (g != one && g != *n).then_some(g)
+}
+
+/// A nontrivial factor of an odd composite n without small prime factors.
+fn find_factor<const NUM_LIMBS: usize>(n: &UnsignedInteger<NUM_LIMBS>) -> UnsignedInteger<NUM_LIMBS> {
+    if let Some(factor) = fermat(n, FERMAT_STEPS) {
+        return factor;
+    }
+    if let Some(factor) = pollard_p_minus_1(n, P_MINUS_1_BOUND) {
+        return factor;
+    }
+    if let Some(factor) = pollard_rho(n, RHO_STEPS) {
+        return factor;
+    }
+    for digits in ECM_DIGITS {
+        if let Some(factor) = ecm(n, &EcmParams::for_digits(digits)) {
+            return factor;
+        }
+    }
+    if let Some(factor) = siqs(n, &SiqsParams::for_bits(n.bits_le())) {
+        return factor;
+    }
+    for digits in LATE_ECM_DIGITS {
+        if let Some(factor) = ecm(n, &EcmParams::for_digits(digits)) {
+            return factor;
+        }
+    }
+    let mut max_steps = 2 * RHO_STEPS;
+    loop {
+        if let Some(factor) = pollard_rho(n, max_steps) {
+            return factor;
+        }
+        max_steps *= 2;
+    }
+}
+
+/// Full factorization of n > 0 as (prime, exponent) pairs sorted by prime.
+pub fn factor<const NUM_LIMBS: usize>(n: &UnsignedInteger<NUM_LIMBS>) -> Vec<(UnsignedInteger<NUM_LIMBS>, u32)> {
+    let one = UnsignedInteger::<NUM_LIMBS>::from_u64(1);
+    assert!(*n != UnsignedInteger::from_u64(0), "cannot factor zero");
+
+    let (mut factors, cofactor) = trial_division(n, TRIAL_DIVISION_BOUND);
+    let mut composites = vec![cofactor];
+    while let Some(m) = composites.pop() {
+        if m == one {
+            continue;
+        }
+        if is_prime_bpsw(&m) {
+            factors.push((m, 1));
+            continue;
+        }
+        let d = find_factor(&m);
+        let (quotient, _) = m.div_rem(&d);
+        composites.push(d);
+        composites.push(quotient);
+    }
+
+    factors.sort();
+    let mut merged: Vec<(UnsignedInteger<NUM_LIMBS>, u32)> = vec![];
+    for (prime, exponent) in factors {
+        match merged.last_mut() {
+            Some((last, e)) if *last == prime => *e += exponent,
+            _ => merged.push((prime, exponent)),
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type U = UnsignedInteger<3>;
+
+    fn factorization(factors: &[(u64, u32)]) -> Vec<(U, u32)> {
+        factors.iter().map(|(p, e)| (U::from_u64(*p), *e)).collect()
+    }
+
+    #[test]
+    fn factor_small_numbers() {
+        assert_eq!(factor(&U::from_u64(1)), vec![]);
+        for n in 2..3000_u64 {
+            let factors = factor(&U::from_u64(n));
+            let mut product = 1;
+            for (p, e) in &factors {
+                assert!(is_prime_bpsw(p));
+                product *= p.limbs[2].pow(*e);
+            }
+            assert_eq!(product, n);
+        }
+        assert_eq!(factor(&U::from_u64(360)), factorization(&[(2, 3), (3, 2), (5, 1)]));
+    }
+
+    #[test]
+    fn fermat_close_primes() {
+        let (p, q) = (9223372036854788173_u128, 9223372036854789197_u128);
+        let n = U::from_u128(p * q);
+        assert_eq!(fermat(&n, 10), Some(U::from_u128(p)));
+        assert_eq!(factor(&n), vec![(U::from_u128(p), 1), (U::from_u128(q), 1)]);
+    }
+
+    #[test]
+    fn pollard_p_minus_1_smooth_prime() {
+        // p - 1 = 2 * 23^2 * 31 * 43 * 53 * 89 * 127 * 163 * 173 * 199, while q - 1 is not smooth
+        let p = U::from_u64(4741024485223396127);
+        let n = U::from_hex_unchecked("10769567dec784659e188d5d1bd21638f1");
+        assert_eq!(pollard_p_minus_1(&n, 600), Some(p));
+        assert_eq!(pollard_p_minus_1(&n, 200), None);
+        let (q, _) = n.div_rem(&p);
+        assert_eq!(factor(&n), vec![(p, 1), (q, 1)]);
+    }
+
+    #[test]
+    fn pollard_rho_and_full_factorization() {
+        let (p, q) = (2558961607_u64, 2420938411_u64);
+        let n = U::from_u128(p as u128 * q as u128);
+        let d = pollard_rho(&n, 1 << 20).unwrap();
+        assert!(d == U::from_u64(p) || d == U::from_u64(q));
+
+        // 12 * 2558961607^2 * 2420938411 * 1352069
+        let n = U::from_hex_unchecked("31898907023c3bf626e3fdc941b3d4");
+        assert_eq!(
+            factor(&n),
+            factorization(&[(2, 2), (3, 1), (1352069, 1), (2420938411, 1), (2558961607, 2)])
+        );
+    }
+}
@@ -0,0 +1,267 @@
+//
+// Hensel lifting: roots of integer polynomials modulo p^k from roots modulo p.
+// Polynomials are coefficient slices, lowest degree first. A root r modulo p^j
+// lifts to r + t p^j, with f(r + t p^j) = f(r) + t p^j f'(r) (mod p^(j + 1)).
+// When f'(r) is a unit there is exactly one t; when p divides f'(r), the root is
+// singular and either every t or none works.
+//
+use lambdaworks_math::unsigned_integer::element::UnsignedInteger;
+
+use crate::{add_mod, crt, mod_inverse, mul_mod, sqrt_mod, sub_mod};
+
+/// f(x) mod m, by Horner's rule.
+fn evaluate<const NUM_LIMBS: usize>(
+        f: &[UnsignedInteger<NUM_LIMBS>],
+        x: &UnsignedInteger<NUM_LIMBS>,
+        m: &UnsignedInteger<NUM_LIMBS>,
+    ) -> UnsignedInteger<NUM_LIMBS> {
+    let (_, x) = x.div_rem(m);
+    f.iter().rev().fold(UnsignedInteger::from_u64(0), |acc, c| add_mod(&mul_mod(&acc, &x, m), &c.div_rem(m).1, m))
+}
+
+/// f'(x) mod m.
+fn evaluate_derivative<const NUM_LIMBS: usize>(
+        f: &[UnsignedInteger<NUM_LIMBS>],
+        x: &UnsignedInteger<NUM_LIMBS>,
+        m: &UnsignedInteger<NUM_LIMBS>,
+    ) -> UnsignedInteger<NUM_LIMBS> {
+    let derivative: Vec<UnsignedInteger<NUM_LIMBS>> = f.iter().enumerate().skip(1)
+        .map(|(i, c)| mul_mod(&UnsignedInteger::from_u64(i as u64).div_rem(m).1, &c.div_rem(m).1, m))
+        .collect();
+    evaluate(&derivative, x, m)
+}
+
+/// The roots of f modulo p^k that reduce to `root` modulo p, in increasing order.
+/// A nonsingular root (f'(root) != 0 mod p) has exactly one lift. A singular root
+/// has either none or p lifts at each step, all of which are enumerated, so p must
+/// fit in a limb in that case. Panics if p^k overflows or k = 0.
+pub fn hensel_lift<const NUM_LIMBS: usize>(
+        f: &[UnsignedInteger<NUM_LIMBS>],
+        root: &UnsignedInteger<NUM_LIMBS>,
+        p: &UnsignedInteger<NUM_LIMBS>,
+        k: u32,
+    ) -> Vec<UnsignedInteger<NUM_LIMBS>> {
+    let zero = UnsignedInteger::<NUM_LIMBS>::from_u64(0);
+    assert!(k > 0, "k must be positive");
+
+    let (_, root) = root.div_rem(p);
+    if evaluate(f, &root, p) != zero {
+        return vec![];
+    }
+    let derivative = evaluate_derivative(f, &root, p);
+    let mut roots = vec![root];
+    // p^j, for roots modulo p^j
+    let mut power = *p;
+    for _ in 1..k {
+        let next_power = power * p;
+        if derivative != zero {
+            // t = -(f(r) / p^j) / f'(r) (mod p)
+            let inverse = mod_inverse(derivative, p).unwrap();
+            roots = roots.iter().map(|r| {
+                let (quotient, _) = evaluate(f, r, &next_power).div_rem(&power);
+                let t = sub_mod(&zero, &mul_mod(&quotient.div_rem(p).1, &inverse, p), p);
+                *r + t * power
+            }).collect();
+        } else {
+            assert!(p.limbs[..NUM_LIMBS - 1].iter().all(|limb| *limb == 0), "too many lifts of a singular root");
+            roots = roots.iter()
+                .filter(|r| evaluate(f, r, &next_power) == zero)
+                .flat_map(|r| (0..p.limbs[NUM_LIMBS - 1]).map(move |t| *r + UnsignedInteger::from_u64(t) * power))
+                .collect();
+        }
+        power = next_power;
+    }
+    roots.sort();
+    roots
+}
+
+/// A square root of a unit b modulo 2^e, or `None` if there is none.
+/// Squares of odd numbers are 1 mod 8, and y = 1 (mod 4) is lifted bit by bit:
+/// if y^2 != b (mod 2^(j + 1)), then (y + 2^(j - 1))^2 = y^2 + 2^j (mod 2^(j + 1)).
+fn sqrt_mod_power_of_two<const NUM_LIMBS: usize>(
+        b: &UnsignedInteger<NUM_LIMBS>,
+        e: u32,
+    ) -> Option<UnsignedInteger<NUM_LIMBS>> {
+    let one = UnsignedInteger::<NUM_LIMBS>::from_u64(1);
+    let low_bits = b.limbs[NUM_LIMBS - 1] & 7;
+    let required = match e {
+        1 => 1,
+        2 => low_bits & 3,
+        _ => low_bits,
+    };
+    if required != 1 {
+        return None;
+    }
+    let mut y = one;
+    for j in 3..e {
+        let modulus = one << (j as usize + 1);
+        if mul_mod(&y, &y, &modulus) != b.div_rem(&modulus).1 {
+            y = y + (one << (j as usize - 1));
+        }
+    }
+    Some(y)
+}
+
+/// A square root of `a` modulo p^k for a prime p, or `None` if `a` is not a square.
+/// With a = p^v b and p not dividing b, v must be even (or at least k), and the root
+/// is p^(v / 2) times a root of b modulo p^(k - v), lifted from `sqrt_mod` by Hensel's
+/// lemma for odd p. Returns the smaller of the roots r and p^k - r.
+/// Panics if p^k overflows or k = 0.
+pub fn sqrt_mod_prime_power<const NUM_LIMBS: usize>(
+        a: &UnsignedInteger<NUM_LIMBS>,
+        p: &UnsignedInteger<NUM_LIMBS>,
+        k: u32,
+    ) -> Option<UnsignedInteger<NUM_LIMBS>> {
+    let zero = UnsignedInteger::<NUM_LIMBS>::from_u64(0);
+    let one = UnsignedInteger::<NUM_LIMBS>::from_u64(1);
+    assert!(k > 0, "k must be positive");
+
+    let modulus = (0..k).fold(one, |power, _| power * p);
+    let (_, mut b) = a.div_rem(&modulus);
+    if b == zero {
+        return Some(zero);
+    }
+    let mut v = 0;
+    loop {
+        let (quotient, remainder) = b.div_rem(p);
+        if remainder != zero {
+            break;
+        }
+        b = quotient;
+        v += 1;
+    }
+    if v % 2 == 1 {
+        return None;
+    }
+    let e = k - v;
+    let y = if *p == UnsignedInteger::from_u64(2) {
+        sqrt_mod_power_of_two(&b, e)?
+    } else {
+        let (root, _) = sqrt_mod(&b, p)?;
+        let power = (0..e).fold(one, |power, _| power * p);
+        let f = [sub_mod(&zero, &b.div_rem(&power).1, &power), zero, one];
+        hensel_lift(&f, &root, p, e)[0]
+    };
+    let root = (0..v / 2).fold(y, |root, _| root * p);
+    let other = sub_mod(&zero, &root, &modulus);
+    Some(root.min(other))
+}
+
+/// A square root of `a` modulo n, given the factorization of n as (prime, exponent)
+/// pairs, or `None` if `a` is not a square modulo n or n does not fit in NUM_LIMBS
+/// limbs. Entries with exponent 0 stand for p^0 = 1 and are skipped. The roots modulo
+/// each prime power are combined by the Chinese remainder theorem.
+pub fn sqrt_mod_composite<const NUM_LIMBS: usize>(
+        a: &UnsignedInteger<NUM_LIMBS>,
+        factorization: &[(UnsignedInteger<NUM_LIMBS>, u32)],
+    ) -> Option<UnsignedInteger<NUM_LIMBS>> {
+    let zero = UnsignedInteger::<NUM_LIMBS>::from_u64(0);
+    let one = UnsignedInteger::<NUM_LIMBS>::from_u64(1);
+    let mut congruences = Vec::with_capacity(factorization.len());
+    for (p, e) in factorization.iter().filter(|(_, e)| *e > 0) {
+        let mut power = one;
+        for _ in 0..*e {
+            let (hi, lo) = UnsignedInteger::mul(&power, p);
+            if hi != zero {
+                return None;
+            }
+            power = lo;
+        }
+        congruences.push((sqrt_mod_prime_power(a, p, *e)?, power));
+    }
+    // the product of the prime powers may still overflow
+    let (root, _) = crt(&congruences).ok()?;
+    Some(root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type U = UnsignedInteger<2>;
+
+    fn u(values: &[u64]) -> Vec<U> {
+        values.iter().map(|v| U::from_u64(*v)).collect()
+    }
+
+    /// All x < m with f(x) = 0 (mod m).
+    fn brute_force_roots(f: &[U], m: u64) -> Vec<U> {
+        (0..m).map(U::from_u64).filter(|x| evaluate(f, x, &U::from_u64(m)) == U::from_u64(0)).collect()
+    }
+
+    #[test]
+    fn lifts_match_brute_force() {
+        // x^3 + 2x + 5, x^2 + 1, x^4 + x^2 + 3 and the singular x^2
+        let polynomials = [u(&[5, 2, 0, 1]), u(&[1, 0, 1]), u(&[3, 0, 1, 0, 1]), u(&[0, 0, 1])];
+        for f in &polynomials {
+            for (p, k) in [(2_u64, 5), (3, 4), (5, 3), (7, 4)] {
+                let mut lifted: Vec<U> = (0..p)
+                    .flat_map(|r| hensel_lift(f, &U::from_u64(r), &U::from_u64(p), k))
+                    .collect();
+                lifted.sort();
+                assert_eq!(lifted, brute_force_roots(f, p.pow(k)), "p = {p}, k = {k}");
+            }
+        }
+    }
+
+    #[test]
+    fn nonsingular_lift_modulo_large_prime_power() {
+        // x^2 = 13 modulo 1000003^5, lifted from a root modulo 1000003
+        let p = U::from_u64(1_000_003);
+        let modulus = U::from_u128(1_000_003_u128.pow(5));
+        let (root, _) = sqrt_mod(&U::from_u64(13), &p).unwrap();
+        let f = [modulus - U::from_u64(13), U::from_u64(0), U::from_u64(1)];
+        let lifted = hensel_lift(&f, &root, &p, 5);
+        assert_eq!(lifted.len(), 1);
+        assert_eq!(mul_mod(&lifted[0], &lifted[0], &modulus), U::from_u64(13));
+        assert_eq!(lifted[0].div_rem(&p).1, root);
+    }
+
+    #[test]
+    fn square_roots_modulo_prime_powers() {
+        for (p, k) in [(2_u64, 1), (2, 2), (2, 3), (2, 7), (3, 5), (5, 3), (13, 2)] {
+            let modulus = p.pow(k);
+            for a in 0..modulus {
+                let squares: Vec<u64> = (0..modulus).filter(|x| x * x % modulus == a).collect();
+                let root = sqrt_mod_prime_power(&U::from_u64(a), &U::from_u64(p), k);
+                match root {
+                    Some(r) => {
+                        let r = r.limbs[1];
+                        assert!(squares.contains(&r) && r <= modulus - r, "a = {a} mod {p}^{k}");
+                    }
+                    None => assert!(squares.is_empty(), "a = {a} mod {p}^{k}"),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn square_roots_modulo_composites() {
+        // n = 2^3 * 3^2 * 7 = 504
+        let factorization = [(U::from_u64(2), 3), (U::from_u64(3), 2), (U::from_u64(7), 1)];
+        for a in 0..504_u64 {
+            let is_square = (0..504_u64).any(|x| x * x % 504 == a);
+            match sqrt_mod_composite(&U::from_u64(a), &factorization) {
+                Some(r) => assert_eq!(r.limbs[1] * r.limbs[1] % 504, a),
+                None => assert!(!is_square, "a = {a}"),
+            }
+        }
+        // 2^61 - 1 and 1000003^2, beyond a single limb
+        let factorization = [(U::from_u64((1 << 61) - 1), 1), (U::from_u64(1_000_003), 2)];
+        let n = U::from_u128(((1_u128 << 61) - 1) * 1_000_003 * 1_000_003);
+        let a = mul_mod(&U::from_u64(0x243f6a8885a308d3), &U::from_u64(0x243f6a8885a308d3), &n);
+        let r = sqrt_mod_composite(&a, &factorization).unwrap();
+        assert_eq!(mul_mod(&r, &r, &n), a);
+    }
+
+    #[test]
+    fn degenerate_factorizations() {
+        // 3^0 * 5 = 5, and 4 = 2^2 = 3^2 (mod 5)
+        let r = sqrt_mod_composite(&U::from_u64(4), &[(U::from_u64(3), 0), (U::from_u64(5), 1)]).unwrap();
+        assert_eq!(r.limbs[1] * r.limbs[1] % 5, 4);
+        // 3^100 does not fit in 128 bits, and (2^61 - 1)^2 does but not its product with 1000003
+        assert_eq!(sqrt_mod_composite(&U::from_u64(4), &[(U::from_u64(3), 100)]), None);
+        let factorization = [(U::from_u64((1 << 61) - 1), 2), (U::from_u64(1_000_003), 1)];
+        assert_eq!(sqrt_mod_composite(&U::from_u64(4), &factorization), None);
+    }
+}
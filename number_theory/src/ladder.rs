@@ -0,0 +1,93 @@
+use core::hint::black_box;
+
+use lambdaworks_math::unsigned_integer::element::UnsignedInteger;
+
+use crate::{MontgomeryContext, LIMB_SIZE_BIT};
+
+/// Swaps `a` and `b` if `swap` is 1 and leaves them untouched if it is 0,
+/// without branching on `swap`.
+pub fn conditional_swap<const NUM_LIMBS: usize>(
+        a: &mut UnsignedInteger<NUM_LIMBS>,
+        b: &mut UnsignedInteger<NUM_LIMBS>,
+        swap: u64,
+    ) {
+    let mask = black_box(swap.wrapping_neg());
+    for i in 0..NUM_LIMBS {
+        let t = mask & (a.limbs[i] ^ b.limbs[i]);
+        a.limbs[i] ^= t;
+        b.limbs[i] ^= t;
+    }
+}
+
+impl<const NUM_LIMBS: usize> MontgomeryContext<NUM_LIMBS> {
+    /// Same as `pow`, but the running time does not depend on `exp`:
+    /// Montgomery ladder over all the NUM_LIMBS * 64 bits of the exponent,
+    /// with branch-free conditional swaps and multiplications.
+    pub fn pow_ct(
+            &self,
+            base: &UnsignedInteger<NUM_LIMBS>,
+            exp: &UnsignedInteger<NUM_LIMBS>,
+        ) -> UnsignedInteger<NUM_LIMBS> {
+        //
+        // Invariant: r1 = r0 * base.
+        //
+        let mut r0 = self.one();
+        let mut r1 = *base;
+        for limb in exp.limbs {
+            for bit in (0..LIMB_SIZE_BIT).rev() {
+                let swap = (limb >> bit) & 1;
+                conditional_swap(&mut r0, &mut r1, swap);
+                r1 = self.mul_ct(&r0, &r1);
+                r0 = self.mul_ct(&r0, &r0);
+                conditional_swap(&mut r0, &mut r1, swap);
+            }
+        }
+        r0
+    }
+}
+
+/// Modular exponentiation for secret exponents, in time independent of `exp`.
+/// Panics if the modulus is even.
+pub fn power_mod_ct<const NUM_LIMBS: usize>(
+        base: UnsignedInteger<NUM_LIMBS>,
+        exp: UnsignedInteger<NUM_LIMBS>,
+        modulus: &UnsignedInteger<NUM_LIMBS>,
+    ) -> UnsignedInteger<NUM_LIMBS> {
+    let ctx = MontgomeryContext::new(modulus).expect("power_mod_ct requires an odd modulus");
+    ctx.from_mont(&ctx.pow_ct(&ctx.to_mont(&base), &exp))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::power_mod;
+
+    #[test]
+    fn conditional_swap_works() {
+        let (x, y) = (UnsignedInteger::<2>::from_u128(7), UnsignedInteger::<2>::from_u128(u128::MAX));
+        let (mut a, mut b) = (x, y);
+        conditional_swap(&mut a, &mut b, 0);
+        assert_eq!((a, b), (x, y));
+        conditional_swap(&mut a, &mut b, 1);
+        assert_eq!((a, b), (y, x));
+    }
+
+    #[test]
+    fn power_mod_ct_matches_power_mod() {
+        let modulus = UnsignedInteger::<4>::from_hex_unchecked(
+            "73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001"
+        );
+        let base = UnsignedInteger::<4>::from_hex_unchecked(
+            "e4840ac57f86f5e293b1d67bc8de5d9a12a70a615d0b8e4d2fc5e69ac5db47f"
+        );
+        for exp in [
+            UnsignedInteger::<4>::from_u64(0),
+            UnsignedInteger::<4>::from_u64(1),
+            UnsignedInteger::<4>::from_u64(64),
+            modulus - UnsignedInteger::from_u64(2),
+            UnsignedInteger::<4>::from_limbs([u64::MAX; 4]),
+        ] {
+            assert_eq!(power_mod_ct(base, exp, &modulus), power_mod(base, exp, &modulus));
+        }
+    }
+}
@@ -1,34 +1,246 @@
 use lambdaworks_math::unsigned_integer::element::UnsignedInteger;
 
+pub mod arithmetic;
+pub mod barrett;
+pub mod continued_fraction;
+pub mod crt;
+pub mod dlog;
+pub mod ecm;
+pub mod factor;
+pub mod hensel;
+pub mod ladder;
+pub mod lucas;
+pub mod montgomery;
+pub mod ntt;
+pub mod order;
+pub mod poly;
+pub mod primality;
+pub mod quadratic;
+pub mod ring;
+pub mod roots;
+pub mod sieve;
+pub mod siqs;
+pub mod window;
+
+pub use arithmetic::{
+    carmichael_lambda, carmichael_lambda_from_factorization, divisor_count, divisor_count_from_factorization,
+    divisor_sum, divisor_sum_from_factorization, euler_phi, euler_phi_from_factorization,
+};
+pub use barrett::BarrettContext;
+pub use continued_fraction::{wiener_attack, ContinuedFraction, Convergents};
+pub use crt::{crt, CrtError};
+pub use hensel::{hensel_lift, sqrt_mod_composite, sqrt_mod_prime_power};
+pub use ladder::power_mod_ct;
+pub use lucas::{is_strong_lucas_probable_prime, lucas_lehmer, lucas_sequence};
+pub use montgomery::MontgomeryContext;
+pub use ntt::NttContext;
+pub use order::{find_primitive_root, is_primitive_root, multiplicative_order, root_of_unity};
+pub use primality::{is_prime_bpsw, is_probable_prime, is_strong_probable_prime};
+pub use quadratic::{cornacchia, jacobi, legendre, sqrt_mod, sqrt_mod_cipolla, sum_of_two_squares};
+pub use ring::{ModularRing, Reducer};
+pub use roots::{icbrt, iroot, is_perfect_power, isqrt};
+pub use sieve::{prime_pi, PrimeIterator};
+pub use window::{multi_operate_with_self, multi_power_mod, power_mod_fixed_window, power_mod_window};
+
+const LIMB_SIZE_BIT: usize = 64;
+
+/// Returns the i-th bit of `a`, counting from the least significant one.
+pub(crate) fn bit_at<const NUM_LIMBS: usize>(a: &UnsignedInteger<NUM_LIMBS>, i: usize) -> bool {
+    (a.limbs[NUM_LIMBS - 1 - i / LIMB_SIZE_BIT] >> (i % LIMB_SIZE_BIT)) & 1 == 1
+}
+
+/// Remainder of the division of `a` by a single limb `d`.
+pub(crate) fn rem_u64<const NUM_LIMBS: usize>(a: &UnsignedInteger<NUM_LIMBS>, d: u64) -> u64 {
+    a.limbs.iter().fold(0_u64, |rem, limb| ((((rem as u128) << 64) | *limb as u128) % d as u128) as u64)
+}
+
+/// Quotient and remainder of the division of `a` by a single limb `d`.
+pub(crate) fn div_u64<const NUM_LIMBS: usize>(
+        a: &UnsignedInteger<NUM_LIMBS>,
+        d: u64,
+    ) -> (UnsignedInteger<NUM_LIMBS>, u64) {
+    let mut quotient = [0_u64; NUM_LIMBS];
+    let mut rem = 0_u128;
+    for (q, limb) in quotient.iter_mut().zip(a.limbs) {
+        let current = (rem << 64) | limb as u128;
+        *q = (current / d as u128) as u64;
+        rem = current % d as u128;
+    }
+    (UnsignedInteger::from_limbs(quotient), rem as u64)
+}
+
+/// Number of trailing zero bits of a nonzero `a`.
+pub(crate) fn trailing_zeros<const NUM_LIMBS: usize>(a: &UnsignedInteger<NUM_LIMBS>) -> usize {
+    let mut zeros = 0;
+    for limb in a.limbs.iter().rev() {
+        if *limb != 0 {
+            return zeros + limb.trailing_zeros() as usize;
+        }
+        zeros += LIMB_SIZE_BIT;
+    }
+    zeros
+}
+
+/// Greatest common divisor, by the binary (Stein's) algorithm.
+pub fn gcd<const NUM_LIMBS: usize>(
+        a: &UnsignedInteger<NUM_LIMBS>,
+        b: &UnsignedInteger<NUM_LIMBS>,
+    ) -> UnsignedInteger<NUM_LIMBS> {
+    let zero = UnsignedInteger::<NUM_LIMBS>::from_u64(0);
+    if *a == zero {
+        return *b;
+    }
+    if *b == zero {
+        return *a;
+    }
+    let shift = trailing_zeros(&(*a | *b));
+    let mut a = *a >> trailing_zeros(a);
+    let mut b = *b;
+    loop {
+        b = b >> trailing_zeros(&b);
+        if a > b {
+            (a, b) = (b, a);
+        }
+        b = b - a;
+        if b == zero {
+            return a << shift;
+        }
+    }
+}
+
+/// Least common multiple, zero if either argument is. Panics if it overflows.
+pub fn lcm<const NUM_LIMBS: usize>(
+        a: &UnsignedInteger<NUM_LIMBS>,
+        b: &UnsignedInteger<NUM_LIMBS>,
+    ) -> UnsignedInteger<NUM_LIMBS> {
+    let zero = UnsignedInteger::<NUM_LIMBS>::from_u64(0);
+    if *a == zero || *b == zero {
+        return zero;
+    }
+    let (quotient, _) = a.div_rem(&gcd(a, b));
+    quotient * b
+}
+
+/// Computes `a + b mod modulus` for `a, b < modulus`, without overflowing.
+pub fn add_mod<const NUM_LIMBS: usize>(
+        a: &UnsignedInteger<NUM_LIMBS>,
+        b: &UnsignedInteger<NUM_LIMBS>,
+        modulus: &UnsignedInteger<NUM_LIMBS>,
+    ) -> UnsignedInteger<NUM_LIMBS> {
+    let (sum, carry) = UnsignedInteger::add(a, b);
+    if carry || sum >= *modulus {
+        UnsignedInteger::sub(&sum, modulus).0
+    } else {
+        sum
+    }
+}
+
+/// Computes `a - b mod modulus` for `a, b < modulus`.
+pub fn sub_mod<const NUM_LIMBS: usize>(
+        a: &UnsignedInteger<NUM_LIMBS>,
+        b: &UnsignedInteger<NUM_LIMBS>,
+        modulus: &UnsignedInteger<NUM_LIMBS>,
+    ) -> UnsignedInteger<NUM_LIMBS> {
+    if a >= b {
+        a - b
+    } else {
+        modulus - b + a
+    }
+}
+
+/// Reduces the double-width integer `hi * 2^(64 * NUM_LIMBS) + lo` modulo `modulus`.
+pub fn reduce_wide<const NUM_LIMBS: usize>(
+        hi: &UnsignedInteger<NUM_LIMBS>,
+        lo: &UnsignedInteger<NUM_LIMBS>,
+        modulus: &UnsignedInteger<NUM_LIMBS>,
+    ) -> UnsignedInteger<NUM_LIMBS> {
+    //
+    // Binary long division: the bits of lo are shifted into the
+    // remainder one at a time, most significant bit first.
+    // The remainder is always below the modulus, so doubling it can
+    // overflow NUM_LIMBS limbs by at most one bit; in that case the true
+    // value lies in [modulus, 2 * modulus) and the wrapping subtraction
+    // yields the correct residue.
+    //
+    let (_, mut rem) = hi.div_rem(modulus);
+    for limb in lo.limbs {
+        for bit in (0..LIMB_SIZE_BIT).rev() {
+            let (doubled, carry) = UnsignedInteger::add(&rem, &rem);
+            rem = doubled;
+            rem.limbs[NUM_LIMBS - 1] |= (limb >> bit) & 1;
+            if carry || rem >= *modulus {
+                (rem, _) = UnsignedInteger::sub(&rem, modulus);
+            }
+        }
+    }
+    rem
+}
+
+/// Divides the double-width integer `hi * 2^(64 * NUM_LIMBS) + lo` by `divisor`,
+/// returning (quotient, remainder), or `None` if the quotient does not fit in NUM_LIMBS limbs.
+pub(crate) fn div_wide<const NUM_LIMBS: usize>(
+        hi: &UnsignedInteger<NUM_LIMBS>,
+        lo: &UnsignedInteger<NUM_LIMBS>,
+        divisor: &UnsignedInteger<NUM_LIMBS>,
+    ) -> Option<(UnsignedInteger<NUM_LIMBS>, UnsignedInteger<NUM_LIMBS>)> {
+    // Same long division as `reduce_wide`, recording the quotient bits.
+    let (quotient_hi, mut rem) = hi.div_rem(divisor);
+    if quotient_hi != UnsignedInteger::from_u64(0) {
+        return None;
+    }
+    let mut quotient = UnsignedInteger::<NUM_LIMBS>::from_u64(0);
+    for limb in lo.limbs {
+        for bit in (0..LIMB_SIZE_BIT).rev() {
+            let (doubled, carry) = UnsignedInteger::add(&rem, &rem);
+            rem = doubled;
+            rem.limbs[NUM_LIMBS - 1] |= (limb >> bit) & 1;
+            quotient = quotient << 1;
+            if carry || rem >= *divisor {
+                (rem, _) = UnsignedInteger::sub(&rem, divisor);
+                quotient.limbs[NUM_LIMBS - 1] |= 1;
+            }
+        }
+    }
+    Some((quotient, rem))
+}
+
+/// Computes `a * b mod modulus` without overflowing, for any modulus
+/// that fits in NUM_LIMBS limbs.
+pub fn mul_mod<const NUM_LIMBS: usize>(
+        a: &UnsignedInteger<NUM_LIMBS>,
+        b: &UnsignedInteger<NUM_LIMBS>,
+        modulus: &UnsignedInteger<NUM_LIMBS>,
+    ) -> UnsignedInteger<NUM_LIMBS> {
+    let (hi, lo) = UnsignedInteger::mul(a, b);
+    reduce_wide(&hi, &lo, modulus)
+}
+
 pub fn power_mod<const NUM_LIMBS: usize>(
-        mut base: UnsignedInteger<NUM_LIMBS>,
+        base: UnsignedInteger<NUM_LIMBS>,
         mut exp: UnsignedInteger<NUM_LIMBS>,
         modulus: &UnsignedInteger<NUM_LIMBS>,
     ) -> UnsignedInteger<NUM_LIMBS> {
     //
     // Fast modular powering algorithm.
-    // Actually div_rem is not to efficient, so Montgomery Arithmetics are preferred.
+    // Products are computed in double width and then reduced, so the
+    // modulus may use all NUM_LIMBS limbs.
+    // Actually the reduction is not too efficient, so Montgomery Arithmetics are preferred.
     //
     let zero = UnsignedInteger::<NUM_LIMBS>::from_u64(0);
     let one = UnsignedInteger::<NUM_LIMBS>::from_u64(1);
 
-    if exp == zero {
-        one
-    } else if exp == one {
-        base
-    } else {
-        let mut result = one;
-        while exp > one {
-            if exp & one == one {
-                (_, result) = (result * base).div_rem(modulus);
-                exp = exp - one;
-            }
-            (_, base) = (base * base).div_rem(modulus);
-            exp >>= 1;
+    let (_, mut base) = base.div_rem(modulus);
+    let (_, mut result) = one.div_rem(modulus);
+    while exp > zero {
+        if exp & one == one {
+            result = mul_mod(&result, &base, modulus);
+        }
+        exp >>= 1;
+        if exp > zero {
+            base = mul_mod(&base, &base, modulus);
         }
-        (_, result) = (base * result).div_rem(modulus);
-        result
     }
+    result
 }
 
 pub fn extended_euclidean_algorithm<const NUM_LIMBS: usize>(
@@ -72,6 +284,71 @@ pub fn extended_euclidean_algorithm<const NUM_LIMBS: usize>(
     (r0, s0, t0)
 }
 
+/// Bézout identity a * s + b * t = gcd(a, b), with the coefficients stored
+/// as magnitudes and explicit sign flags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bezout<const NUM_LIMBS: usize> {
+    pub gcd: UnsignedInteger<NUM_LIMBS>,
+    pub s: UnsignedInteger<NUM_LIMBS>,
+    pub s_is_negative: bool,
+    pub t: UnsignedInteger<NUM_LIMBS>,
+    pub t_is_negative: bool,
+}
+
+pub fn xgcd<const NUM_LIMBS: usize>(
+        a: UnsignedInteger<NUM_LIMBS>, b: UnsignedInteger<NUM_LIMBS>,
+    ) -> Bezout<NUM_LIMBS> {
+    //
+    // Extended Euclidean algorithm on the magnitudes of the coefficients.
+    // The signs alternate, so after n steps s has sign (-1)^n and t has
+    // sign (-1)^(n + 1). Magnitudes are bounded by a and b.
+    //
+    let zero = UnsignedInteger::<NUM_LIMBS>::from_u64(0);
+    let one = UnsignedInteger::<NUM_LIMBS>::from_u64(1);
+
+    let (mut r0, mut r1) = (a, b);
+    let (mut s0, mut s1) = (one, zero);
+    let (mut t0, mut t1) = (zero, one);
+    let mut n: u64 = 0;
+
+    while r1 != zero {
+        let (q, r) = r0.div_rem(&r1);
+        (r0, r1) = (r1, r);
+        (s0, s1) = (s1, s0 + q * s1);
+        (t0, t1) = (t1, t0 + q * t1);
+        n += 1;
+    }
+
+    Bezout {
+        gcd: r0,
+        s: s0,
+        s_is_negative: n & 1 == 1 && s0 != zero,
+        t: t0,
+        t_is_negative: n & 1 == 0 && t0 != zero,
+    }
+}
+
+/// Inverse of `a` modulo `modulus`, or `None` when they are not coprime.
+pub fn mod_inverse<const NUM_LIMBS: usize>(
+        a: UnsignedInteger<NUM_LIMBS>,
+        modulus: &UnsignedInteger<NUM_LIMBS>,
+    ) -> Option<UnsignedInteger<NUM_LIMBS>> {
+    let one = UnsignedInteger::<NUM_LIMBS>::from_u64(1);
+
+    let (_, a) = a.div_rem(modulus);
+    let bezout = xgcd(a, *modulus);
+    if bezout.gcd != one {
+        return None;
+    }
+    // a * s = 1 (mod modulus)
+    let (_, s) = bezout.s.div_rem(modulus);
+    if bezout.s_is_negative {
+        Some(*modulus - s)
+    } else {
+        Some(s)
+    }
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -86,4 +363,122 @@ mod tests {
         let obtained = power_mod(b, exp, &modulus);
         assert_eq!(obtained, expected);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn mul_mod_works_full_width_modulus() {
+        // modulus uses the full 128 bits of 2 limbs
+        let modulus = UnsignedInteger::<2>::from_u128(u128::MAX - 158);
+        let a = UnsignedInteger::<2>::from_u128(u128::MAX - 1000);
+        let b = UnsignedInteger::<2>::from_u128(u128::MAX - 2000);
+        // (m - 842) * (m - 1842) = 842 * 1842 (mod m)
+        let expected = UnsignedInteger::<2>::from_u128(842 * 1842);
+        assert_eq!(mul_mod(&a, &b, &modulus), expected);
+    }
+
+    #[test]
+    fn power_mod_works_full_width_modulus() {
+        // 2^127 - 1 is a Mersenne prime, so by Fermat a^(p - 1) = 1 (mod p)
+        let p = UnsignedInteger::<2>::from_u128((1 << 127) - 1);
+        let exp = UnsignedInteger::<2>::from_u128((1 << 127) - 2);
+        let one = UnsignedInteger::<2>::from_u64(1);
+        for a in [2_u128, 3, 0xdeadbeefdeadbeefdeadbeef] {
+            let base = UnsignedInteger::<2>::from_u128(a);
+            assert_eq!(power_mod(base, exp, &p), one);
+        }
+    }
+
+    #[test]
+    fn power_mod_matches_u64_arithmetic() {
+        let modulus = 0xffff_ffff_ffff_ffc5_u64;
+        let (mut expected, base, exp) = (1_u64, 0x1234_5678_9abc_def0_u64, 100_003_u64);
+        for _ in 0..exp {
+            expected = (expected as u128 * base as u128 % modulus as u128) as u64;
+        }
+        let obtained = power_mod(
+            UnsignedInteger::<1>::from_u64(base),
+            UnsignedInteger::<1>::from_u64(exp),
+            &UnsignedInteger::<1>::from_u64(modulus),
+        );
+        assert_eq!(obtained, UnsignedInteger::<1>::from_u64(expected));
+    }
+
+    #[test]
+    fn xgcd_small_values() {
+        // 240 * (-9) + 46 * 47 = 2
+        let bezout = xgcd(UnsignedInteger::<1>::from_u64(240), UnsignedInteger::<1>::from_u64(46));
+        assert_eq!(bezout, Bezout {
+            gcd: UnsignedInteger::from_u64(2),
+            s: UnsignedInteger::from_u64(9),
+            s_is_negative: true,
+            t: UnsignedInteger::from_u64(47),
+            t_is_negative: false,
+        });
+    }
+
+    #[test]
+    fn xgcd_satisfies_bezout_identity() {
+        use rand::prelude::*;
+
+        let mut rng = rand::thread_rng();
+        let signed = |x: UnsignedInteger<2>, negative: bool| {
+            let x = x.limbs[1] as i128 | (x.limbs[0] as i128) << 64;
+            if negative { -x } else { x }
+        };
+        for _ in 0..1000 {
+            // values of 62 bits, so that every product fits in an i128
+            let a = rng.gen::<u64>() >> 2;
+            let b = rng.gen::<u64>() >> rng.gen_range(2..60);
+            if a == 0 && b == 0 {
+                continue;
+            }
+            let bezout = xgcd(UnsignedInteger::<2>::from_u64(a), UnsignedInteger::<2>::from_u64(b));
+            let s = signed(bezout.s, bezout.s_is_negative);
+            let t = signed(bezout.t, bezout.t_is_negative);
+            let g = signed(bezout.gcd, false);
+            assert_eq!(a as i128 * s + b as i128 * t, g);
+            assert_eq!(a as i128 % g + b as i128 % g, 0);
+        }
+        // gcd(0, 0) = 0, with the trivial coefficients
+        let zero = UnsignedInteger::<2>::from_u64(0);
+        assert_eq!(xgcd(zero, zero), Bezout { gcd: zero, s: UnsignedInteger::from_u64(1), s_is_negative: false, t: zero, t_is_negative: false });
+    }
+
+    #[test]
+    fn div_wide_matches_u128_division() {
+        let divisor = UnsignedInteger::<1>::from_u64(0xfedc_ba98_7654_3211);
+        let (hi, lo) = (UnsignedInteger::<1>::from_u64(0x1234_5678_9abc_def0), UnsignedInteger::<1>::from_u64(u64::MAX - 5));
+        let wide = (0x1234_5678_9abc_def0_u128 << 64) | (u64::MAX - 5) as u128;
+        let (quotient, remainder) = div_wide(&hi, &lo, &divisor).unwrap();
+        assert_eq!(quotient.limbs[0] as u128, wide / 0xfedc_ba98_7654_3211);
+        assert_eq!(remainder.limbs[0] as u128, wide % 0xfedc_ba98_7654_3211);
+        // the quotient needs more than one limb
+        assert_eq!(div_wide(&divisor, &lo, &hi), None);
+    }
+
+    #[test]
+    fn gcd_and_lcm() {
+        let (a, b) = (UnsignedInteger::<2>::from_u64(84), UnsignedInteger::<2>::from_u64(120));
+        assert_eq!(gcd(&a, &b), UnsignedInteger::from_u64(12));
+        assert_eq!(lcm(&a, &b), UnsignedInteger::from_u64(840));
+        let zero = UnsignedInteger::<2>::from_u64(0);
+        assert_eq!(gcd(&a, &zero), a);
+        assert_eq!(lcm(&a, &zero), zero);
+        // the lcm of coprime 64-bit values takes two limbs
+        let (p, q) = (UnsignedInteger::<2>::from_u64(u64::MAX), UnsignedInteger::<2>::from_u64(u64::MAX - 1));
+        assert_eq!(lcm(&p, &q), UnsignedInteger::from_u128(u64::MAX as u128 * (u64::MAX - 1) as u128));
+    }
+
+    #[test]
+    fn mod_inverse_works() {
+        let modulus = UnsignedInteger::<2>::from_u128(u128::MAX - 158);
+        for a in [1_u128, 2, 65537, u128::MAX - 159] {
+            let a = UnsignedInteger::<2>::from_u128(a);
+            let inverse = mod_inverse(a, &modulus).unwrap();
+            assert_eq!(mul_mod(&a, &inverse, &modulus), UnsignedInteger::from_u64(1));
+        }
+        let modulus = UnsignedInteger::<2>::from_u64(240);
+        assert_eq!(mod_inverse(UnsignedInteger::from_u64(46), &modulus), None);
+        assert_eq!(mod_inverse(UnsignedInteger::from_u64(0), &modulus), None);
+        assert_eq!(mod_inverse(UnsignedInteger::from_u64(7), &modulus), Some(UnsignedInteger::from_u64(103)));
+    }
+}
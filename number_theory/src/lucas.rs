@@ -0,0 +1,236 @@
+//
+// Lucas sequences U_k(P, Q) and V_k(P, Q): U_0 = 0, U_1 = 1, V_0 = 2, V_1 = P and
+// X_(j+1) = P X_j - Q X_(j-1). They are evaluated modulo m with the doubling formulas
+//     U_2j = U_j (2 U_(j+1) - P U_j),    U_(2j+1) = U_(j+1)^2 - Q U_j^2,
+// which, unlike those for V, do not divide by 2 or D = P^2 - 4Q and so hold for any m.
+// Then V_k = 2 U_(k+1) - P U_k.
+//
+use lambdaworks_math::unsigned_integer::element::UnsignedInteger;
+
+use crate::primality::{odd_part, trial_division};
+use crate::{add_mod, bit_at, isqrt, jacobi, mul_mod, sub_mod, MontgomeryContext, ModularRing, Reducer};
+
+/// (U_k, V_k, Q^k) modulo m, with P, Q, one and the results in the representation of `mul`.
+fn sequence<const NUM_LIMBS: usize>(
+        p: &UnsignedInteger<NUM_LIMBS>,
+        q: &UnsignedInteger<NUM_LIMBS>,
+        k: &UnsignedInteger<NUM_LIMBS>,
+        one: &UnsignedInteger<NUM_LIMBS>,
+        m: &UnsignedInteger<NUM_LIMBS>,
+        mul: impl Fn(&UnsignedInteger<NUM_LIMBS>, &UnsignedInteger<NUM_LIMBS>) -> UnsignedInteger<NUM_LIMBS>,
+    ) -> (UnsignedInteger<NUM_LIMBS>, UnsignedInteger<NUM_LIMBS>, UnsignedInteger<NUM_LIMBS>) {
+    // (U_j, U_(j+1), Q^j), from j = 0
+    let (mut u, mut u_next, mut q_j) = (UnsignedInteger::from_u64(0), *one, *one);
+    for i in (0..k.bits_le()).rev() {
+        let double_next = add_mod(&u_next, &u_next, m);
+        (u, u_next) = (
+            mul(&u, &sub_mod(&double_next, &mul(p, &u), m)),
+            sub_mod(&mul(&u_next, &u_next), &mul(q, &mul(&u, &u)), m),
+        );
+        q_j = mul(&q_j, &q_j);
+        if bit_at(k, i) {
+            (u, u_next) = (u_next, sub_mod(&mul(p, &u_next), &mul(q, &u), m));
+            q_j = mul(&q_j, q);
+        }
+    }
+    let v = sub_mod(&add_mod(&u_next, &u_next, m), &mul(p, &u), m);
+    (u, v, q_j)
+}
+
+/// Lucas sequence terms (U_k(P, Q), V_k(P, Q)) modulo m > 0, for P and Q given as
+/// residues modulo m, in O(log k) multiplications.
+pub fn lucas_sequence<const NUM_LIMBS: usize>(
+        p: &UnsignedInteger<NUM_LIMBS>,
+        q: &UnsignedInteger<NUM_LIMBS>,
+        k: &UnsignedInteger<NUM_LIMBS>,
+        m: &UnsignedInteger<NUM_LIMBS>,
+    ) -> (UnsignedInteger<NUM_LIMBS>, UnsignedInteger<NUM_LIMBS>) {
+    match Reducer::new(m) {
+        Some(ring) => {
+            let (p, q) = (ring.to_repr(&p.div_rem(m).1), ring.to_repr(&q.div_rem(m).1));
+            let (u, v, _) = sequence(&p, &q, k, &ring.one(), m, |a, b| ring.mul(a, b));
+            (ring.to_standard(&u), ring.to_standard(&v))
+        }
+        None => {
+            let (p, q) = (p.div_rem(m).1, q.div_rem(m).1);
+            let (_, one) = UnsignedInteger::from_u64(1).div_rem(m);
+            let (u, v, _) = sequence(&p, &q, k, &one, m, |a, b| mul_mod(a, b, m));
+            (u, v)
+        }
+    }
+}
+
+/// Reduces a small signed integer modulo n.
+pub(crate) fn signed_mod<const NUM_LIMBS: usize>(a: i64, n: &UnsignedInteger<NUM_LIMBS>) -> UnsignedInteger<NUM_LIMBS> {
+    let (_, a_abs) = UnsignedInteger::<NUM_LIMBS>::from_u64(a.unsigned_abs()).div_rem(n);
+    if a < 0 {
+        sub_mod(&UnsignedInteger::from_u64(0), &a_abs, n)
+    } else {
+        a_abs
+    }
+}
+
+/// Strong Lucas test for odd n > 2 other than 9, with Selfridge's parameters:
+/// D is the first of 5, -7, 9, -11, ... with (D / n) = -1, P = 1 and Q = (1 - D) / 4.
+pub(crate) fn strong_lucas_round<const NUM_LIMBS: usize>(ctx: &MontgomeryContext<NUM_LIMBS>) -> bool {
+    let n = ctx.modulus();
+    let zero = UnsignedInteger::<NUM_LIMBS>::from_u64(0);
+
+    let mut d: i64 = 5;
+    loop {
+        match jacobi(&signed_mod(d, n), n) {
+            -1 => break,
+            // n shares a factor with D, and is prime only if it is |D|
+            0 => return *n == UnsignedInteger::from_u64(d.unsigned_abs()),
+            _ => {}
+        }
+        // D is never found for perfect squares.
+        if d == 13 {
+            let root = isqrt(n);
+            if root * root == *n {
+                return false;
+            }
+        }
+        d = if d > 0 { -(d + 2) } else { -d + 2 };
+    }
+    let q = ctx.to_mont(&signed_mod((1 - d) / 4, n));
+
+    // n + 1 = k * 2^s with k odd. It cannot overflow: 2^(64 NUM_LIMBS) - 1 is a multiple of 5.
+    let (k, s) = odd_part(&(n + UnsignedInteger::from_u64(1)));
+    let (u, mut v, mut q_k) = sequence(&ctx.one(), &q, &k, &ctx.one(), n, |a, b| ctx.mul(a, b));
+
+    // n is a strong Lucas probable prime if U_k = 0 or V_(k 2^r) = 0 for some r < s,
+    // where V_2j = V_j^2 - 2 Q^j
+    if u == zero || v == zero {
+        return true;
+    }
+    for _ in 1..s {
+        v = sub_mod(&ctx.square(&v), &add_mod(&q_k, &q_k, n), n);
+        if v == zero {
+            return true;
+        }
+        q_k = ctx.square(&q_k);
+    }
+    false
+}
+
+/// Strong Lucas probable prime test with Selfridge's parameters. Primes always pass,
+/// and composites rarely do; the first strong Lucas pseudoprime is 5459.
+pub fn is_strong_lucas_probable_prime<const NUM_LIMBS: usize>(n: &UnsignedInteger<NUM_LIMBS>) -> bool {
+    if *n < UnsignedInteger::from_u64(3) || n.limbs[NUM_LIMBS - 1] & 1 == 0 {
+        return *n == UnsignedInteger::from_u64(2);
+    }
+    let root = isqrt(n);
+    if root * root == *n {
+        return false;
+    }
+    strong_lucas_round(&MontgomeryContext::new(n).unwrap())
+}
+
+/// Lucas-Lehmer test: whether the Mersenne number 2^exponent - 1 is prime. With
+/// s_0 = 4 and s_(i+1) = s_i^2 - 2, that is s_i = V_(2^i)(4, 1), an odd exponent p
+/// gives a prime if and only if s_(p-2) = 0 (mod 2^p - 1).
+/// Panics if 2^exponent - 1 does not fit in NUM_LIMBS limbs.
+pub fn lucas_lehmer<const NUM_LIMBS: usize>(exponent: usize) -> bool {
+    let bits = 64 * NUM_LIMBS;
+    assert!(exponent <= bits, "2^{exponent} - 1 does not fit in {NUM_LIMBS} limbs");
+    if exponent == 2 {
+        return true;
+    }
+    // a composite exponent gives a composite Mersenne number
+    if trial_division(&UnsignedInteger::<1>::from_u64(exponent as u64)) != Some(true) {
+        return false;
+    }
+    let mersenne = UnsignedInteger::<NUM_LIMBS>::from_limbs([u64::MAX; NUM_LIMBS]) >> (bits - exponent);
+    let ctx = MontgomeryContext::new(&mersenne).unwrap();
+    let two = ctx.to_mont(&UnsignedInteger::from_u64(2));
+    let mut s = ctx.to_mont(&UnsignedInteger::from_u64(4));
+    for _ in 0..exponent - 2 {
+        s = sub_mod(&ctx.square(&s), &two, &mersenne);
+    }
+    s == UnsignedInteger::from_u64(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// (U_k, V_k) modulo m from the recurrence.
+    fn naive_sequence(p: i64, q: i64, k: u64, m: i64) -> (u64, u64) {
+        let (mut u, mut u_next, mut v, mut v_next) = (0, 1 % m, 2 % m, p.rem_euclid(m));
+        for _ in 0..k {
+            (u, u_next) = (u_next, (p * u_next - q * u).rem_euclid(m));
+            (v, v_next) = (v_next, (p * v_next - q * v).rem_euclid(m));
+        }
+        (u as u64, v as u64)
+    }
+
+    #[test]
+    fn sequences_match_recurrence() {
+        // odd moduli use Montgomery and even ones Barrett reduction
+        for m in [1_i64, 2, 97, 100, 1024, 999_983] {
+            for (p, q) in [(1, -1), (3, 2), (1, 2), (-4, 7), (6, 9)] {
+                for k in [0, 1, 2, 3, 10, 57, 200] {
+                    let (u, v) = lucas_sequence(
+                        &signed_mod(p, &UnsignedInteger::<1>::from_u64(m as u64)),
+                        &signed_mod(q, &UnsignedInteger::<1>::from_u64(m as u64)),
+                        &UnsignedInteger::from_u64(k),
+                        &UnsignedInteger::from_u64(m as u64),
+                    );
+                    assert_eq!((u.limbs[0], v.limbs[0]), naive_sequence(p, q, k, m), "P = {p}, Q = {q}, k = {k}, m = {m}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn fibonacci_and_lucas_numbers() {
+        // F_k = U_k(1, -1) and L_k = V_k(1, -1); F_90 and L_90 fit in 64 bits
+        let m = UnsignedInteger::<2>::from_u128(1 << 100);
+        let minus_one = m - UnsignedInteger::from_u64(1);
+        let (fibonacci, lucas) = lucas_sequence(&UnsignedInteger::from_u64(1), &minus_one, &UnsignedInteger::from_u64(90), &m);
+        assert_eq!(fibonacci, UnsignedInteger::from_u64(2_880_067_194_370_816_120));
+        assert_eq!(lucas, UnsignedInteger::from_u64(6_440_026_026_380_244_498));
+    }
+
+    #[test]
+    fn strong_lucas_pseudoprimes_pass_lucas_test() {
+        for n in [5459, 5777, 10877, 16109, 18971, 22499, 24569, 25199, 40309, 58519] {
+            let n = UnsignedInteger::<1>::from_u64(n);
+            assert!(is_strong_lucas_probable_prime(&n));
+            assert!(!crate::is_prime_bpsw(&n));
+        }
+    }
+
+    #[test]
+    fn strong_lucas_test_below_bound() {
+        // the strong Lucas pseudoprimes below 60000 are the ones above
+        let pseudoprimes = [5459, 5777, 10877, 16109, 18971, 22499, 24569, 25199, 40309, 58519];
+        for n in 0..60_000_u64 {
+            let is_prime = n > 1 && (2..).take_while(|d| d * d <= n).all(|d| n % d != 0);
+            let passes = is_strong_lucas_probable_prime(&UnsignedInteger::<1>::from_u64(n));
+            assert_eq!(passes, is_prime || pseudoprimes.contains(&n), "n = {n}");
+        }
+    }
+
+    #[test]
+    fn mersenne_primes() {
+        let exponents: Vec<usize> = (0..=256).filter(|p| lucas_lehmer::<4>(*p)).collect();
+        assert_eq!(exponents, [2, 3, 5, 7, 13, 17, 19, 31, 61, 89, 107, 127]);
+        assert!(lucas_lehmer::<1>(61));
+        assert!(!lucas_lehmer::<1>(64));
+    }
+
+    #[test]
+    fn large_prime_constants() {
+        let bls12_381_subgroup_order = UnsignedInteger::<4>::from_hex_unchecked(
+            "73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001"
+        );
+        let bn254_subgroup_order = UnsignedInteger::<4>::from_hex_unchecked(
+            "30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001"
+        );
+        assert!(is_strong_lucas_probable_prime(&bls12_381_subgroup_order));
+        assert!(is_strong_lucas_probable_prime(&bn254_subgroup_order));
+        assert!(!is_strong_lucas_probable_prime(&(bls12_381_subgroup_order + UnsignedInteger::from_u64(2))));
+    }
+}
@@ -0,0 +1,215 @@
+use lambdaworks_math::unsigned_integer::{
+    element::UnsignedInteger,
+    montgomery::MontgomeryAlgorithms,
+};
+
+use crate::{bit_at, mul_mod, reduce_wide, window::sliding_window_pow};
+
+/// Precomputed constants for Montgomery arithmetic modulo a runtime odd modulus m,
+/// with R = 2^(64 * NUM_LIMBS).
+/// Elements in Montgomery form are represented as a * R mod m.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MontgomeryContext<const NUM_LIMBS: usize> {
+    modulus: UnsignedInteger<NUM_LIMBS>,
+    r: UnsignedInteger<NUM_LIMBS>,
+    r2: UnsignedInteger<NUM_LIMBS>,
+    mu: u64,
+}
+
+impl<const NUM_LIMBS: usize> MontgomeryContext<NUM_LIMBS> {
+    /// Returns `None` if the modulus is even.
+    pub fn new(modulus: &UnsignedInteger<NUM_LIMBS>) -> Option<Self> {
+        let zero = UnsignedInteger::<NUM_LIMBS>::from_u64(0);
+        let one = UnsignedInteger::<NUM_LIMBS>::from_u64(1);
+
+        if modulus.limbs[NUM_LIMBS - 1] & 1 == 0 {
+            return None;
+        }
+
+        // R mod m, where R = 1 * 2^(64 * NUM_LIMBS) + 0.
+        let r = reduce_wide(&one, &zero, modulus);
+        let r2 = mul_mod(&r, &r, modulus);
+
+        // Newton iteration for m^(-1) mod 2^64: each step doubles the
+        // number of correct low bits, and m * m = 1 (mod 8) for odd m.
+        let m0 = modulus.limbs[NUM_LIMBS - 1];
+        let mut inv = m0;
+        for _ in 0..5 {
+            inv = inv.wrapping_mul(2_u64.wrapping_sub(m0.wrapping_mul(inv)));
+        }
+
+        Some(Self {
+            modulus: *modulus,
+            r,
+            r2,
+            mu: inv.wrapping_neg(),
+        })
+    }
+
+    pub fn modulus(&self) -> &UnsignedInteger<NUM_LIMBS> {
+        &self.modulus
+    }
+
+    /// Montgomery form of 1, that is R mod m.
+    pub fn one(&self) -> UnsignedInteger<NUM_LIMBS> {
+        self.r
+    }
+
+    pub fn to_mont(&self, a: &UnsignedInteger<NUM_LIMBS>) -> UnsignedInteger<NUM_LIMBS> {
+        let (_, a) = a.div_rem(&self.modulus);
+        self.mul(&a, &self.r2)
+    }
+
+    pub fn from_mont(&self, a: &UnsignedInteger<NUM_LIMBS>) -> UnsignedInteger<NUM_LIMBS> {
+        self.mul(a, &UnsignedInteger::from_u64(1))
+    }
+
+    /// Montgomery product a * b * R^(-1) mod m.
+    pub fn mul(
+            &self,
+            a: &UnsignedInteger<NUM_LIMBS>,
+            b: &UnsignedInteger<NUM_LIMBS>,
+        ) -> UnsignedInteger<NUM_LIMBS> {
+        MontgomeryAlgorithms::cios(a, b, &self.modulus, &self.mu)
+    }
+
+    /// Product a * b mod m of integers in standard form, with two Montgomery products.
+    pub fn mul_mod(
+            &self,
+            a: &UnsignedInteger<NUM_LIMBS>,
+            b: &UnsignedInteger<NUM_LIMBS>,
+        ) -> UnsignedInteger<NUM_LIMBS> {
+        self.mul(&self.mul(a, b), &self.r2)
+    }
+
+    pub fn square(&self, a: &UnsignedInteger<NUM_LIMBS>) -> UnsignedInteger<NUM_LIMBS> {
+        self.mul(a, a)
+    }
+
+    /// Same as `mul`, but the running time does not depend on the values of `a` and `b`.
+    /// This is the CIOS algorithm with a masked final subtraction instead of a branch.
+    pub fn mul_ct(
+            &self,
+            a: &UnsignedInteger<NUM_LIMBS>,
+            b: &UnsignedInteger<NUM_LIMBS>,
+        ) -> UnsignedInteger<NUM_LIMBS> {
+        let q = &self.modulus.limbs;
+        let mut t = [0_u64; NUM_LIMBS];
+        let mut t_extra = [0_u64; 2];
+        for i in (0..NUM_LIMBS).rev() {
+            // t := t + a * b[i]
+            let mut c = 0_u128;
+            for j in (0..NUM_LIMBS).rev() {
+                let cs = t[j] as u128 + (a.limbs[j] as u128) * (b.limbs[i] as u128) + c;
+                c = cs >> 64;
+                t[j] = cs as u64;
+            }
+            let cs = t_extra[1] as u128 + c;
+            t_extra[0] = (cs >> 64) as u64;
+            t_extra[1] = cs as u64;
+
+            // t := (t + m * q) / 2^64
+            let m = t[NUM_LIMBS - 1].wrapping_mul(self.mu) as u128;
+            let mut c = (t[NUM_LIMBS - 1] as u128 + m * (q[NUM_LIMBS - 1] as u128)) >> 64;
+            for j in (0..NUM_LIMBS - 1).rev() {
+                let cs = t[j] as u128 + m * (q[j] as u128) + c;
+                c = cs >> 64;
+                t[j + 1] = cs as u64;
+            }
+            let cs = t_extra[1] as u128 + c;
+            t[0] = cs as u64;
+            t_extra[1] = t_extra[0] + (cs >> 64) as u64;
+        }
+
+        // subtract the modulus if t >= q, selecting the result with a mask.
+        let t = UnsignedInteger { limbs: t };
+        let (reduced, borrow) = t.sbb(&self.modulus, 0);
+        // keep t only when the subtraction borrowed and there was no overflow.
+        let keep = borrow & (t_extra[1] ^ 1).wrapping_neg();
+        let mut limbs = reduced.limbs;
+        for (limb, t_limb) in limbs.iter_mut().zip(t.limbs) {
+            *limb ^= keep & (*limb ^ t_limb);
+        }
+        UnsignedInteger { limbs }
+    }
+
+    /// Raises `base`, given in Montgomery form, to `exp`.
+    /// The result is also in Montgomery form.
+    pub fn pow(
+            &self,
+            base: &UnsignedInteger<NUM_LIMBS>,
+            exp: &UnsignedInteger<NUM_LIMBS>,
+        ) -> UnsignedInteger<NUM_LIMBS> {
+        let mut result = self.r;
+        for i in (0..exp.bits_le()).rev() {
+            result = self.square(&result);
+            if bit_at(exp, i) {
+                result = self.mul(&result, base);
+            }
+        }
+        result
+    }
+
+    /// Same as `pow`, using a sliding window of W bits.
+    pub fn pow_window<const W: usize>(
+            &self,
+            base: &UnsignedInteger<NUM_LIMBS>,
+            exp: &UnsignedInteger<NUM_LIMBS>,
+        ) -> UnsignedInteger<NUM_LIMBS> {
+        sliding_window_pow::<_, W, NUM_LIMBS>(base, exp, &self.r, |a, b| self.mul(a, b))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::power_mod;
+
+    #[test]
+    fn even_modulus_is_rejected() {
+        let modulus = UnsignedInteger::<2>::from_u128(2147483648);
+        assert!(MontgomeryContext::new(&modulus).is_none());
+    }
+
+    #[test]
+    fn mont_round_trip() {
+        let modulus = UnsignedInteger::<2>::from_u128(u128::MAX - 158);
+        let ctx = MontgomeryContext::new(&modulus).unwrap();
+        let a = UnsignedInteger::<2>::from_u128(0x1234_5678_9abc_def0_1234_5678_9abc_def0);
+        assert_eq!(ctx.from_mont(&ctx.to_mont(&a)), a);
+        assert_eq!(ctx.from_mont(&ctx.one()), UnsignedInteger::from_u64(1));
+    }
+
+    #[test]
+    fn mul_and_pow_match_power_mod() {
+        // BLS12-381 subgroup order
+        let r = UnsignedInteger::<4>::from_hex_unchecked(
+            "73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001"
+        );
+        let ctx = MontgomeryContext::new(&r).unwrap();
+        let a = UnsignedInteger::<4>::from_u64(5);
+        let b = UnsignedInteger::<4>::from_hex_unchecked("0xe4840ac57f86f5e293b1d67bc8de5d9a");
+        let product = ctx.from_mont(&ctx.mul(&ctx.to_mont(&a), &ctx.to_mont(&b)));
+        assert_eq!(product, mul_mod(&a, &b, &r));
+        assert_eq!(ctx.mul_mod(&a, &b), mul_mod(&a, &b, &r));
+
+        let exp = r - UnsignedInteger::from_u64(2);
+        let obtained = ctx.from_mont(&ctx.pow(&ctx.to_mont(&b), &exp));
+        assert_eq!(obtained, power_mod(b, exp, &r));
+        let obtained = ctx.from_mont(&ctx.pow_window::<5>(&ctx.to_mont(&b), &exp));
+        assert_eq!(obtained, power_mod(b, exp, &r));
+    }
+
+    #[test]
+    fn mul_ct_matches_mul() {
+        // full-width modulus, so that the CIOS accumulator overflows
+        let modulus = UnsignedInteger::<2>::from_u128(u128::MAX - 158);
+        let ctx = MontgomeryContext::new(&modulus).unwrap();
+        let mut a = UnsignedInteger::<2>::from_u128(u128::MAX - 1000);
+        let b = UnsignedInteger::<2>::from_u128(0x1234_5678_9abc_def0_1234_5678_9abc_def0);
+        for _ in 0..1000 {
+            assert_eq!(ctx.mul_ct(&a, &b), ctx.mul(&a, &b));
+            a = ctx.mul(&a, &b);
+        }
+    }
+}
@@ -0,0 +1,351 @@
+//
+// Number-theoretic transform over Z/pZ for a prime p = k 2^n + 1, which has
+// primitive 2^m-th roots of unity for every m <= n. Polynomials are vectors of
+// coefficients in standard form, lowest degree first. Data stays in standard
+// form while twiddle factors are kept in Montgomery form, so that a single
+// Montgomery product x * (w R) R^(-1) = x w needs no conversions.
+//
+use lambdaworks_math::unsigned_integer::element::UnsignedInteger;
+
+use crate::{add_mod, is_prime_bpsw, jacobi, mod_inverse, power_mod, sub_mod, trailing_zeros, MontgomeryContext};
+
+/// Reorders `values`, whose length is a power of two, by bit-reversed index.
+pub fn bit_reverse_permutation<T>(values: &mut [T]) {
+    let n = values.len();
+    assert!(n.is_power_of_two(), "length must be a power of two");
+    let log_n = n.trailing_zeros();
+    if log_n == 0 {
+        return;
+    }
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - log_n);
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+}
+
+/// Roots of unity of power-of-two order modulo a prime p = k 2^n + 1.
+#[derive(Debug, Clone)]
+pub struct NttContext<const NUM_LIMBS: usize> {
+    ctx: MontgomeryContext<NUM_LIMBS>,
+    /// `roots[m]` is a primitive 2^m-th root of unity in Montgomery form, for m <= n,
+    /// and `inverse_roots[m]` its inverse.
+    roots: Vec<UnsignedInteger<NUM_LIMBS>>,
+    inverse_roots: Vec<UnsignedInteger<NUM_LIMBS>>,
+}
+
+impl<const NUM_LIMBS: usize> NttContext<NUM_LIMBS> {
+    /// Context for the prime `p`. With p - 1 = k 2^n, g^k is a primitive 2^n-th root of
+    /// unity for any quadratic non-residue g, since g^((p - 1) / 2) = -1, so k need not
+    /// be factored. Returns `None` if p is 2 or not prime.
+    pub fn new(p: &UnsignedInteger<NUM_LIMBS>) -> Option<Self> {
+        let one = UnsignedInteger::<NUM_LIMBS>::from_u64(1);
+        let two = UnsignedInteger::<NUM_LIMBS>::from_u64(2);
+
+        if *p <= two || !is_prime_bpsw(p) {
+            return None;
+        }
+        let ctx = MontgomeryContext::new(p)?;
+        let two_adicity = trailing_zeros(&(*p - one));
+        let k = (*p - one) >> two_adicity;
+        let mut non_residue = two;
+        while jacobi(&non_residue, p) != -1 {
+            non_residue = non_residue + one;
+        }
+        let root = power_mod(non_residue, k, p);
+        let inverse_root = mod_inverse(root, p)?;
+
+        // halve the order by squaring, from 2^n down to 1
+        let mut roots = vec![ctx.to_mont(&root)];
+        let mut inverse_roots = vec![ctx.to_mont(&inverse_root)];
+        for _ in 0..two_adicity {
+            roots.push(ctx.square(roots.last().unwrap()));
+            inverse_roots.push(ctx.square(inverse_roots.last().unwrap()));
+        }
+        roots.reverse();
+        inverse_roots.reverse();
+        Some(Self { ctx, roots, inverse_roots })
+    }
+
+    pub fn modulus(&self) -> &UnsignedInteger<NUM_LIMBS> {
+        self.ctx.modulus()
+    }
+
+    /// Largest supported transform length, 2^n.
+    pub fn max_len(&self) -> usize {
+        1 << (self.roots.len() - 1).min(usize::BITS as usize - 1)
+    }
+
+    /// Primitive `len`-th root of unity in standard form.
+    /// Panics if `len` is not a power of two or exceeds `max_len`.
+    pub fn root_of_unity(&self, len: usize) -> UnsignedInteger<NUM_LIMBS> {
+        self.ctx.from_mont(&self.roots[self.log_len(len)])
+    }
+
+    fn log_len(&self, len: usize) -> usize {
+        assert!(len.is_power_of_two(), "length must be a power of two");
+        let log_len = len.trailing_zeros() as usize;
+        assert!(log_len < self.roots.len(), "length exceeds the two-adicity of the modulus");
+        log_len
+    }
+
+    /// Iterative radix-2 Cooley-Tukey transform with the given root table.
+    fn transform(&self, values: &mut [UnsignedInteger<NUM_LIMBS>], roots: &[UnsignedInteger<NUM_LIMBS>]) {
+        let n = values.len();
+        let log_n = self.log_len(n);
+        if n == 1 {
+            return;
+        }
+        // twiddles[j] = w^j for the n-th root w, in Montgomery form
+        let mut twiddles = vec![self.ctx.one()];
+        for j in 1..n / 2 {
+            twiddles.push(self.ctx.mul(&twiddles[j - 1], &roots[log_n]));
+        }
+
+        bit_reverse_permutation(values);
+        let modulus = self.modulus();
+        let mut half = 1;
+        while half < n {
+            let stride = n / (2 * half);
+            for block in values.chunks_mut(2 * half) {
+                let (low, high) = block.split_at_mut(half);
+                for (j, (a, b)) in low.iter_mut().zip(high.iter_mut()).enumerate() {
+                    let t = self.ctx.mul(b, &twiddles[j * stride]);
+                    *b = sub_mod(a, &t, modulus);
+                    *a = add_mod(a, &t, modulus);
+                }
+            }
+            half *= 2;
+        }
+    }
+
+    /// Evaluations of the polynomial at 1, w, ..., w^(n - 1) for the n-th root w,
+    /// where n = `values.len()`, in place.
+    /// Panics if n is not a power of two or exceeds `max_len`.
+    pub fn forward(&self, values: &mut [UnsignedInteger<NUM_LIMBS>]) {
+        self.transform(values, &self.roots);
+    }
+
+    /// Inverse of `forward`: interpolates the coefficients from the evaluations, in place.
+    pub fn inverse(&self, values: &mut [UnsignedInteger<NUM_LIMBS>]) {
+        self.transform(values, &self.inverse_roots);
+        let n = UnsignedInteger::from_u64(values.len() as u64);
+        let n_inverse = self.ctx.to_mont(&mod_inverse(n, self.modulus()).expect("length is invertible"));
+        for value in values.iter_mut() {
+            *value = self.ctx.mul(value, &n_inverse);
+        }
+    }
+
+    /// Product of two polynomials, by pointwise multiplication of their transforms.
+    /// Panics if the product has more than `max_len` coefficients.
+    pub fn multiply(
+            &self,
+            a: &[UnsignedInteger<NUM_LIMBS>],
+            b: &[UnsignedInteger<NUM_LIMBS>],
+        ) -> Vec<UnsignedInteger<NUM_LIMBS>> {
+        if a.is_empty() || b.is_empty() {
+            return vec![];
+        }
+        let result_len = a.len() + b.len() - 1;
+        let n = result_len.next_power_of_two();
+        let zero = UnsignedInteger::from_u64(0);
+
+        let mut a_values = a.to_vec();
+        a_values.resize(n, zero);
+        let mut b_values = b.to_vec();
+        b_values.resize(n, zero);
+        self.forward(&mut a_values);
+        self.forward(&mut b_values);
+        // a * b in standard form is mul(a, mont(b)) = mul(mul(a, b), R^2)
+        for (x, y) in a_values.iter_mut().zip(&b_values) {
+            *x = self.ctx.mul_mod(x, y);
+        }
+        self.inverse(&mut a_values);
+        a_values.truncate(result_len);
+        a_values
+    }
+
+    /// Inverse of the polynomial f modulo x^len by Newton iteration,
+    /// g -> g (2 - f g) mod x^(2k). Requires f(0) != 0.
+    fn inverse_series(&self, f: &[UnsignedInteger<NUM_LIMBS>], len: usize) -> Vec<UnsignedInteger<NUM_LIMBS>> {
+        let modulus = self.modulus();
+        let two = UnsignedInteger::from_u64(2);
+        let mut g = vec![mod_inverse(f[0], modulus).expect("constant term is invertible")];
+        let mut k = 1;
+        while k < len {
+            k *= 2;
+            let f_truncated = &f[..f.len().min(k)];
+            let mut correction = self.multiply(f_truncated, &g);
+            correction.truncate(k);
+            // 2 - f g
+            for c in correction.iter_mut() {
+                *c = sub_mod(&UnsignedInteger::from_u64(0), c, modulus);
+            }
+            correction[0] = add_mod(&correction[0], &two, modulus);
+            g = self.multiply(&g, &correction);
+            g.truncate(k);
+        }
+        g.truncate(len);
+        g
+    }
+
+    /// Quotient and remainder of a by b, from the inverse of the reversed divisor:
+    /// rev(q) = rev(a) / rev(b) mod x^(deg a - deg b + 1).
+    /// Trailing zero coefficients are ignored and the remainder has length deg b.
+    /// Panics if b is zero.
+    pub fn divide(
+            &self,
+            a: &[UnsignedInteger<NUM_LIMBS>],
+            b: &[UnsignedInteger<NUM_LIMBS>],
+        ) -> (Vec<UnsignedInteger<NUM_LIMBS>>, Vec<UnsignedInteger<NUM_LIMBS>>) {
+        let zero = UnsignedInteger::from_u64(0);
+        let modulus = self.modulus();
+        let degree = |p: &[UnsignedInteger<NUM_LIMBS>]| p.iter().rposition(|c| *c != zero);
+
+        let b_degree = degree(b).expect("division by the zero polynomial");
+        let a = &a[..degree(a).map_or(0, |d| d + 1)];
+        if a.len() <= b_degree {
+            return (vec![], a.to_vec());
+        }
+        let quotient_len = a.len() - b_degree;
+
+        let b_reversed: Vec<_> = b[..=b_degree].iter().rev().copied().collect();
+        let a_reversed: Vec<_> = a.iter().rev().take(quotient_len).copied().collect();
+        let mut quotient = self.multiply(&a_reversed, &self.inverse_series(&b_reversed, quotient_len));
+        quotient.truncate(quotient_len);
+        quotient.reverse();
+
+        let mut remainder = self.multiply(&b[..=b_degree], &quotient);
+        remainder.truncate(b_degree);
+        for (r, c) in remainder.iter_mut().zip(a) {
+            *r = sub_mod(c, r, modulus);
+        }
+        (quotient, remainder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mul_mod;
+
+    type U = UnsignedInteger<4>;
+
+    /// 998244353 = 119 * 2^23 + 1
+    fn ntt_prime() -> U {
+        U::from_u64(998244353)
+    }
+
+    fn schoolbook(a: &[U], b: &[U], modulus: &U) -> Vec<U> {
+        let mut product = vec![U::from_u64(0); a.len() + b.len() - 1];
+        for (i, x) in a.iter().enumerate() {
+            for (j, y) in b.iter().enumerate() {
+                product[i + j] = add_mod(&product[i + j], &mul_mod(x, y, modulus), modulus);
+            }
+        }
+        product
+    }
+
+    fn pseudo_random(len: usize, seed: u64, modulus: &U) -> Vec<U> {
+        (0..len as u64).map(|i| U::from_u64((i + seed).wrapping_mul(0x9e3779b97f4a7c15)).div_rem(modulus).1).collect()
+    }
+
+    #[test]
+    fn bit_reversal() {
+        let mut values: Vec<usize> = (0..8).collect();
+        bit_reverse_permutation(&mut values);
+        assert_eq!(values, [0, 4, 2, 6, 1, 5, 3, 7]);
+        let mut single = [42];
+        bit_reverse_permutation(&mut single);
+        assert_eq!(single, [42]);
+    }
+
+    #[test]
+    fn forward_evaluates_at_powers_of_the_root() {
+        let p = ntt_prime();
+        let ntt = NttContext::new(&p).unwrap();
+        assert_eq!(ntt.max_len(), 1 << 23);
+        let coefficients = pseudo_random(16, 1, &p);
+        let mut values = coefficients.clone();
+        ntt.forward(&mut values);
+        let w = ntt.root_of_unity(16);
+        for (i, value) in values.iter().enumerate() {
+            let x = crate::power_mod(w, U::from_u64(i as u64), &p);
+            let expected = coefficients.iter().rev().fold(U::from_u64(0), |acc, c| add_mod(&mul_mod(&acc, &x, &p), c, &p));
+            assert_eq!(*value, expected);
+        }
+        ntt.inverse(&mut values);
+        assert_eq!(values, coefficients);
+    }
+
+    #[test]
+    fn multiply_matches_schoolbook() {
+        // a prime of the NTT form and the BLS12-381 scalar field
+        let primes = [
+            ntt_prime(),
+            U::from_hex_unchecked("73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001"),
+        ];
+        for p in primes {
+            let ntt = NttContext::new(&p).unwrap();
+            for (len_a, len_b) in [(1, 1), (1, 7), (5, 3), (16, 16), (33, 20)] {
+                let a = pseudo_random(len_a, 3, &p);
+                let b = pseudo_random(len_b, 11, &p);
+                assert_eq!(ntt.multiply(&a, &b), schoolbook(&a, &b, &p));
+            }
+            assert!(ntt.multiply(&[], &[U::from_u64(1)]).is_empty());
+        }
+    }
+
+    #[test]
+    fn divide_inverts_multiply() {
+        let p = ntt_prime();
+        let ntt = NttContext::new(&p).unwrap();
+        for (len_a, len_b) in [(1, 1), (10, 1), (10, 4), (40, 17), (17, 17), (5, 9)] {
+            let a = pseudo_random(len_a, 5, &p);
+            let b = pseudo_random(len_b, 7, &p);
+            let (quotient, remainder) = ntt.divide(&a, &b);
+            assert!(remainder.len() < len_b);
+            // a = b q + r
+            let mut reconstructed = if quotient.is_empty() { vec![] } else { ntt.multiply(&b, &quotient) };
+            reconstructed.resize(len_a.max(reconstructed.len()), U::from_u64(0));
+            for (c, r) in reconstructed.iter_mut().zip(&remainder) {
+                *c = add_mod(c, r, &p);
+            }
+            reconstructed.truncate(len_a);
+            assert_eq!(reconstructed, a);
+        }
+        // exact division, with trailing zeros in the dividend
+        let b = pseudo_random(6, 2, &p);
+        let q = pseudo_random(9, 4, &p);
+        let mut a = ntt.multiply(&b, &q);
+        a.extend([U::from_u64(0); 3]);
+        let (quotient, remainder) = ntt.divide(&a, &b);
+        assert_eq!(quotient, q);
+        assert!(remainder.iter().all(|r| *r == U::from_u64(0)));
+    }
+
+    #[test]
+    fn unsupported_moduli() {
+        assert!(NttContext::new(&U::from_u64(2)).is_none());
+        assert!(NttContext::new(&U::from_u64(1 << 20)).is_none());
+        // 2^2 + 1 = 5 only supports lengths up to 4
+        assert_eq!(NttContext::new(&U::from_u64(5)).unwrap().max_len(), 4);
+        // composites, including 3 * 5 * 2^32 + 1 and a product of two NTT primes
+        assert!(NttContext::new(&U::from_u64(9)).is_none());
+        assert!(NttContext::new(&U::from_u64(21)).is_none());
+        assert!(NttContext::new(&U::from_u64(15 * (1 << 32) + 1)).is_none());
+        assert!(NttContext::new(&U::from_u128(998244353 * 469762049)).is_none());
+    }
+
+    #[test]
+    fn prime_with_large_cofactor() {
+        // (2^89 + 569) 2^32 + 1, where the prime 2^89 + 569 would be slow to factor
+        let p = U::from_hex_unchecked("2000000000000000000023900000001");
+        let ntt = NttContext::new(&p).unwrap();
+        assert_eq!(ntt.max_len(), 1 << 32);
+        let root = ntt.root_of_unity(1 << 32);
+        assert_eq!(power_mod(root, U::from_u64(1 << 32), &p), U::from_u64(1));
+        assert_ne!(power_mod(root, U::from_u64(1 << 31), &p), U::from_u64(1));
+    }
+}
@@ -0,0 +1,235 @@
+use lambdaworks_math::unsigned_integer::element::UnsignedInteger;
+
+use crate::{is_prime_bpsw, power_mod, xgcd};
+
+/// Returns true if the factorization, given as (prime, exponent) pairs,
+/// consists of primes and multiplies to `n` without overflowing.
+fn factorization_matches<const NUM_LIMBS: usize>(
+        n: &UnsignedInteger<NUM_LIMBS>,
+        factorization: &[(UnsignedInteger<NUM_LIMBS>, u32)],
+    ) -> bool {
+    let zero = UnsignedInteger::<NUM_LIMBS>::from_u64(0);
+    let mut product = UnsignedInteger::<NUM_LIMBS>::from_u64(1);
+    for (prime, exponent) in factorization {
+        if !is_prime_bpsw(prime) {
+            return false;
+        }
+        for _ in 0..*exponent {
+            let (hi, lo) = UnsignedInteger::mul(&product, prime);
+            if hi != zero {
+                return false;
+            }
+            product = lo;
+        }
+    }
+    product == *n
+}
+
+/// Order of `a` in the multiplicative group modulo `n`, given the factorization of
+/// a multiple of it, such as the group order phi(n) (p - 1 for a prime p).
+/// Returns `None` if `a` is not invertible modulo `n`, if its order does not divide
+/// the factored number, or if the factorization has a non-prime entry or overflows.
+pub fn multiplicative_order<const NUM_LIMBS: usize>(
+        a: &UnsignedInteger<NUM_LIMBS>,
+        n: &UnsignedInteger<NUM_LIMBS>,
+        factorization: &[(UnsignedInteger<NUM_LIMBS>, u32)],
+    ) -> Option<UnsignedInteger<NUM_LIMBS>> {
+    let zero = UnsignedInteger::<NUM_LIMBS>::from_u64(0);
+    let one = UnsignedInteger::<NUM_LIMBS>::from_u64(1);
+    let (_, reduced_one) = one.div_rem(n);
+
+    if xgcd(*a, *n).gcd != one {
+        return None;
+    }
+    let mut order = one;
+    for (prime, exponent) in factorization {
+        if !is_prime_bpsw(prime) {
+            return None;
+        }
+        for _ in 0..*exponent {
+            let (hi, lo) = UnsignedInteger::mul(&order, prime);
+            if hi != zero {
+                return None;
+            }
+            order = lo;
+        }
+    }
+    if power_mod(*a, order, n) != reduced_one {
+        return None;
+    }
+    //
+    // Remove each prime factor q from the order while a^(order / q) = 1.
+    //
+    for (prime, exponent) in factorization {
+        for _ in 0..*exponent {
+            let (reduced_order, _) = order.div_rem(prime);
+            if power_mod(*a, reduced_order, n) != reduced_one {
+                break;
+            }
+            order = reduced_order;
+        }
+    }
+    Some(order)
+}
+
+/// Returns true if `g` generates the multiplicative group modulo the prime `p`,
+/// given the factorization of p - 1. A composite p, or a factorization that does
+/// not match p - 1, cannot certify anything, so it yields false.
+pub fn is_primitive_root<const NUM_LIMBS: usize>(
+        g: &UnsignedInteger<NUM_LIMBS>,
+        p: &UnsignedInteger<NUM_LIMBS>,
+        factorization: &[(UnsignedInteger<NUM_LIMBS>, u32)],
+    ) -> bool {
+    let zero = UnsignedInteger::<NUM_LIMBS>::from_u64(0);
+    let one = UnsignedInteger::<NUM_LIMBS>::from_u64(1);
+    let p_minus_one = *p - one;
+
+    if !is_prime_bpsw(p) {
+        return false;
+    }
+    let (_, g) = g.div_rem(p);
+    if g == zero || !factorization_matches(&p_minus_one, factorization) {
+        return false;
+    }
+    // g has order p - 1 iff g^((p - 1) / q) != 1 for every prime q dividing p - 1
+    factorization.iter().all(|(prime, _)| {
+        let (exp, _) = p_minus_one.div_rem(prime);
+        power_mod(g, exp, p) != one
+    })
+}
+
+/// Smallest primitive root modulo the prime `p`, given the factorization of p - 1.
+/// Returns `None` if p is not prime or the factorization does not match p - 1.
+pub fn find_primitive_root<const NUM_LIMBS: usize>(
+        p: &UnsignedInteger<NUM_LIMBS>,
+        factorization: &[(UnsignedInteger<NUM_LIMBS>, u32)],
+    ) -> Option<UnsignedInteger<NUM_LIMBS>> {
+    let one = UnsignedInteger::<NUM_LIMBS>::from_u64(1);
+
+    if !is_prime_bpsw(p) || !factorization_matches(&(*p - one), factorization) {
+        return None;
+    }
+    let mut g = one;
+    while g < *p {
+        if is_primitive_root(&g, p, factorization) {
+            return Some(g);
+        }
+        g = g + one;
+    }
+    None
+}
+
+/// Primitive `order`-th root of unity modulo the prime `p`, given the factorization
+/// of p - 1. Returns `None` if p is not prime, `order` does not divide p - 1 or
+/// the factorization does not match p - 1.
+pub fn root_of_unity<const NUM_LIMBS: usize>(
+        order: &UnsignedInteger<NUM_LIMBS>,
+        p: &UnsignedInteger<NUM_LIMBS>,
+        factorization: &[(UnsignedInteger<NUM_LIMBS>, u32)],
+    ) -> Option<UnsignedInteger<NUM_LIMBS>> {
+    let zero = UnsignedInteger::<NUM_LIMBS>::from_u64(0);
+    let one = UnsignedInteger::<NUM_LIMBS>::from_u64(1);
+
+    if *order == zero || !is_prime_bpsw(p) {
+        return None;
+    }
+    let (cofactor, rem) = (*p - one).div_rem(order);
+    if rem != zero {
+        return None;
+    }
+    let generator = find_primitive_root(p, factorization)?;
+    Some(power_mod(generator, cofactor, p))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type U = UnsignedInteger<4>;
+
+    fn bls12_381_r() -> U {
+        U::from_hex_unchecked("73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001")
+    }
+
+    fn bls12_381_r_minus_one_factorization() -> Vec<(U, u32)> {
+        [
+            (2, 32), (3, 1), (11, 1), (19, 1), (10177, 1), (125527, 1), (859267, 1), (906349, 2),
+            (2508409, 1), (2529403, 1), (52437899, 1), (254760293, 2),
+        ].iter().map(|(p, e)| (U::from_u64(*p), *e)).collect()
+    }
+
+    #[test]
+    fn order_modulo_17() {
+        // 2 generates {1, 2, 4, 8, 16, 15, 13, 9}, and 3 is a primitive root
+        let p = U::from_u64(17);
+        let factorization = [(U::from_u64(2), 4)];
+        assert_eq!(multiplicative_order(&U::from_u64(2), &p, &factorization), Some(U::from_u64(8)));
+        assert_eq!(multiplicative_order(&U::from_u64(16), &p, &factorization), Some(U::from_u64(2)));
+        assert_eq!(multiplicative_order(&U::from_u64(1), &p, &factorization), Some(U::from_u64(1)));
+        assert_eq!(multiplicative_order(&U::from_u64(0), &p, &factorization), None);
+        assert!(!is_primitive_root(&U::from_u64(2), &p, &factorization));
+        assert!(is_primitive_root(&U::from_u64(3), &p, &factorization));
+        assert_eq!(find_primitive_root(&p, &factorization), Some(U::from_u64(3)));
+        // wrong factorization
+        assert_eq!(find_primitive_root(&p, &[(U::from_u64(2), 3)]), None);
+    }
+
+    #[test]
+    fn order_modulo_composite() {
+        // (Z/15Z)* has order phi(15) = 8 and exponent 4
+        let n = U::from_u64(15);
+        let factorization = [(U::from_u64(2), 3)];
+        let orders: Vec<Option<U>> = (0..15).map(|a| multiplicative_order(&U::from_u64(a), &n, &factorization)).collect();
+        let expected: Vec<Option<U>> = [0, 1, 4, 0, 2, 0, 0, 4, 4, 0, 0, 2, 0, 4, 2]
+            .iter().map(|o| (*o != 0).then(|| U::from_u64(*o))).collect();
+        assert_eq!(orders, expected);
+    }
+
+    #[test]
+    fn primitive_roots_by_brute_force() {
+        for (p, factorization) in [(11_u64, vec![(2, 1), (5, 1)]), (13, vec![(2, 2), (3, 1)]), (31, vec![(2, 1), (3, 1), (5, 1)])] {
+            let factorization: Vec<(U, u32)> = factorization.iter().map(|(q, e)| (U::from_u64(*q), *e)).collect();
+            for g in 1..p {
+                let order = (1..p).find(|k| power_mod(U::from_u64(g), U::from_u64(*k), &U::from_u64(p)) == U::from_u64(1));
+                assert_eq!(multiplicative_order(&U::from_u64(g), &U::from_u64(p), &factorization), order.map(U::from_u64));
+                assert_eq!(is_primitive_root(&U::from_u64(g), &U::from_u64(p), &factorization), order == Some(p - 1));
+            }
+        }
+    }
+
+    #[test]
+    fn bls12_381_roots_of_unity() {
+        let r = bls12_381_r();
+        let factorization = bls12_381_r_minus_one_factorization();
+        assert_eq!(find_primitive_root(&r, &factorization), Some(U::from_u64(7)));
+        assert!(!is_primitive_root(&U::from_u64(5), &r, &factorization));
+
+        let root = root_of_unity(&U::from_u64(64), &r, &factorization).unwrap();
+        assert_eq!(power_mod(root, U::from_u64(64), &r), U::from_u64(1));
+        assert_ne!(power_mod(root, U::from_u64(32), &r), U::from_u64(1));
+        assert_eq!(root_of_unity(&U::from_u64(5), &r, &factorization), None);
+    }
+
+    #[test]
+    fn invalid_inputs_give_none() {
+        // 4 is not prime, although 4^2 = 17 - 1
+        assert_eq!(root_of_unity(&U::from_u64(4), &U::from_u64(17), &[(U::from_u64(4), 2)]), None);
+        assert!(!is_primitive_root(&U::from_u64(2), &U::from_u64(17), &[(U::from_u64(4), 2)]));
+        // composite moduli, with correct factorizations of n - 1
+        assert_eq!(root_of_unity(&U::from_u64(4), &U::from_u64(21), &[(U::from_u64(2), 2), (U::from_u64(5), 1)]), None);
+        assert_eq!(root_of_unity(&U::from_u64(2), &U::from_u64(9), &[(U::from_u64(2), 3)]), None);
+        // (Z/21Z)* is not cyclic, although 2^4 = 16 and 2^10 = 16 are not 1 modulo 21
+        let factorization = [(U::from_u64(2), 2), (U::from_u64(5), 1)];
+        assert!(!is_primitive_root(&U::from_u64(2), &U::from_u64(21), &factorization));
+        assert_eq!(find_primitive_root(&U::from_u64(21), &factorization), None);
+    }
+
+    #[test]
+    fn invalid_order_factorizations_give_none() {
+        // 16 has order 2 modulo 17, which removing the non-prime 4 cannot find
+        let p = U::from_u64(17);
+        assert_eq!(multiplicative_order(&U::from_u64(16), &p, &[(U::from_u64(4), 2)]), None);
+        // 2^300 does not fit in 4 limbs
+        assert_eq!(multiplicative_order(&U::from_u64(16), &p, &[(U::from_u64(2), 300)]), None);
+    }
+}
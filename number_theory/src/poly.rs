@@ -0,0 +1,434 @@
+//
+// Polynomials over a prime field GF(p), as lambdaworks `Polynomial`s with
+// coefficients in any `IsPrimeField`, from `U64PrimeField` to 4-limb fields.
+// Factoring goes through the usual three stages: squarefree factorization,
+// distinct-degree factorization and Cantor-Zassenhaus equal-degree splitting.
+// Powers x^(p^i) mod f are obtained by modular composition with x^p mod f,
+// since g(x)^p = g(x^p) over GF(p), so p only has to be raised to once.
+//
+use std::cmp::Ordering;
+
+use lambdaworks_math::field::{element::FieldElement, traits::IsPrimeField};
+use lambdaworks_math::polynomial::Polynomial;
+use lambdaworks_math::unsigned_integer::traits::IsUnsignedInteger;
+use rand::prelude::*;
+
+type Poly<F> = Polynomial<FieldElement<F>>;
+
+/// The polynomial x.
+fn x<F: IsPrimeField>() -> Poly<F> {
+    Polynomial::new(&[FieldElement::zero(), FieldElement::one()])
+}
+
+fn is_one<F: IsPrimeField>(a: &Poly<F>) -> bool {
+    a.coefficients.len() == 1 && a.coefficients[0] == FieldElement::one()
+}
+
+/// Quotient and remainder of a by b, by long division. Panics if b is zero.
+pub fn div_rem<F: IsPrimeField>(a: &Poly<F>, b: &Poly<F>) -> (Poly<F>, Poly<F>) {
+    let b = Polynomial::new(&b.coefficients);
+    let lead_inverse = b.coefficients.last().expect("division by the zero polynomial").inv().unwrap();
+    let b_degree = b.coefficients.len() - 1;
+
+    let mut remainder = Polynomial::new(&a.coefficients).coefficients;
+    if remainder.len() <= b_degree {
+        return (Polynomial::zero(), Polynomial::new(&remainder));
+    }
+    let mut quotient = vec![FieldElement::zero(); remainder.len() - b_degree];
+    for i in (0..quotient.len()).rev() {
+        let c = &remainder[i + b_degree] * &lead_inverse;
+        if c != FieldElement::zero() {
+            for (j, b_j) in b.coefficients.iter().enumerate() {
+                remainder[i + j] = &remainder[i + j] - &c * b_j;
+            }
+        }
+        quotient[i] = c;
+    }
+    remainder.truncate(b_degree);
+    (Polynomial::new(&quotient), Polynomial::new(&remainder))
+}
+
+fn rem<F: IsPrimeField>(a: &Poly<F>, b: &Poly<F>) -> Poly<F> {
+    div_rem(a, b).1
+}
+
+fn mul_mod<F: IsPrimeField>(a: &Poly<F>, b: &Poly<F>, modulus: &Poly<F>) -> Poly<F> {
+    rem(&a.mul_with_ref(b), modulus)
+}
+
+/// a divided by its leading coefficient. The zero polynomial is left as is.
+pub fn monic<F: IsPrimeField>(a: &Poly<F>) -> Poly<F> {
+    let a = Polynomial::new(&a.coefficients);
+    match a.coefficients.last() {
+        Some(lead) => a.scale_coeffs(&lead.inv().unwrap()),
+        None => a,
+    }
+}
+
+/// Monic greatest common divisor, by Euclid's algorithm. It is zero only if a = b = 0.
+pub fn gcd<F: IsPrimeField>(a: &Poly<F>, b: &Poly<F>) -> Poly<F> {
+    let (mut a, mut b) = (Polynomial::new(&a.coefficients), Polynomial::new(&b.coefficients));
+    while !b.coefficients.is_empty() {
+        (a, b) = (b.clone(), rem(&a, &b));
+    }
+    monic(&a)
+}
+
+/// Formal derivative.
+pub fn derivative<F: IsPrimeField>(a: &Poly<F>) -> Poly<F> {
+    let coefficients: Vec<FieldElement<F>> = a.coefficients.iter().enumerate().skip(1)
+        .map(|(i, c)| c * FieldElement::from(i as u64))
+        .collect();
+    Polynomial::new(&coefficients)
+}
+
+/// base^exp mod `modulus`, by square-and-multiply over the bits of any unsigned exponent.
+pub fn pow_mod<F: IsPrimeField, T: IsUnsignedInteger>(base: &Poly<F>, mut exp: T, modulus: &Poly<F>) -> Poly<F> {
+    let (zero, one) = (T::from(0), T::from(1));
+    let mut result = rem(&Polynomial::new(&[FieldElement::one()]), modulus);
+    let mut power = rem(base, modulus);
+    while exp != zero {
+        if exp & one == one {
+            result = mul_mod(&result, &power, modulus);
+        }
+        exp >>= 1;
+        if exp != zero {
+            power = mul_mod(&power, &power, modulus);
+        }
+    }
+    result
+}
+
+/// Modular composition g(h) mod f, by Horner's rule.
+pub fn compose_mod<F: IsPrimeField>(g: &Poly<F>, h: &Poly<F>, f: &Poly<F>) -> Poly<F> {
+    let h = rem(h, f);
+    g.coefficients.iter().rev().fold(Polynomial::zero(), |acc, c| {
+        rem(&(mul_mod(&acc, &h, f) + Polynomial::new(std::slice::from_ref(c))), f)
+    })
+}
+
+/// x^p mod f.
+fn frobenius<F: IsPrimeField>(f: &Poly<F>) -> Poly<F> {
+    let p = F::modulus_minus_one() + F::RepresentativeType::from(1);
+    pow_mod(&x(), p, f)
+}
+
+fn is_characteristic_two<F: IsPrimeField>() -> bool {
+    F::modulus_minus_one() == F::RepresentativeType::from(1)
+}
+
+/// Uniformly distributed element, up to a bias below 2^(-64).
+fn random_element<F: IsPrimeField>(rng: &mut impl Rng) -> FieldElement<F> {
+    let radix = FieldElement::<F>::from(u64::MAX) + FieldElement::<F>::one();
+    (0..F::field_bit_size().div_ceil(64) + 1).fold(FieldElement::zero(), |acc, _| {
+        acc * &radix + FieldElement::from(rng.gen::<u64>())
+    })
+}
+
+/// Factorization of f into (monic) squarefree polynomials s_i, with f = lead prod s_i^i.
+/// In characteristic p, a zero derivative means f(x) = g(x^p) = g(x)^p.
+pub fn squarefree_factorization<F: IsPrimeField>(f: &Poly<F>) -> Vec<(Poly<F>, u32)> {
+    let f = monic(f);
+    if f.coefficients.len() <= 1 {
+        return vec![];
+    }
+    let f_prime = derivative(&f);
+    if f_prime.coefficients.is_empty() {
+        return p_th_root_factorization(&f);
+    }
+
+    let mut factors = vec![];
+    let mut c = gcd(&f, &f_prime);
+    let mut w = div_rem(&f, &c).0;
+    let mut i = 1;
+    // w is the product of the factors of multiplicity at least i that p does not divide
+    while !is_one(&w) {
+        let y = gcd(&w, &c);
+        let factor = div_rem(&w, &y).0;
+        if !is_one(&factor) {
+            factors.push((factor, i));
+        }
+        w = y;
+        c = div_rem(&c, &w).0;
+        i += 1;
+    }
+    // what remains has multiplicities divisible by p
+    if !is_one(&c) {
+        factors.extend(p_th_root_factorization(&c));
+    }
+    factors
+}
+
+/// Squarefree factorization of f = g(x^p) = g(x)^p, where p <= deg f.
+fn p_th_root_factorization<F: IsPrimeField>(f: &Poly<F>) -> Vec<(Poly<F>, u32)> {
+    let p = (2..=f.degree()).find(|i| FieldElement::<F>::from(*i as u64) == FieldElement::zero()).unwrap();
+    let root: Vec<FieldElement<F>> = f.coefficients.iter().step_by(p).cloned().collect();
+    squarefree_factorization(&Polynomial::new(&root))
+        .into_iter()
+        .map(|(factor, i)| (factor, i * p as u32))
+        .collect()
+}
+
+/// Splits a squarefree polynomial into (g_d, d), where g_d is the monic product of
+/// its irreducible factors of degree d, for each d that occurs.
+pub fn distinct_degree_factorization<F: IsPrimeField>(f: &Poly<F>) -> Vec<(Poly<F>, usize)> {
+    let mut f = monic(f);
+    let mut factors = vec![];
+    let mut x_p = frobenius(&f);
+    // h = x^(p^d) mod f
+    let mut h = x_p.clone();
+    let mut d = 1;
+    while 2 * d <= f.degree() {
+        let g = gcd(&(h.clone() - x()), &f);
+        if !is_one(&g) {
+            f = div_rem(&f, &g).0;
+            h = rem(&h, &f);
+            x_p = rem(&x_p, &f);
+            factors.push((g, d));
+        }
+        h = compose_mod(&h, &x_p, &f);
+        d += 1;
+    }
+    if f.degree() > 0 {
+        let degree = f.degree();
+        factors.push((f, degree));
+    }
+    factors
+}
+
+/// Cantor-Zassenhaus: the monic irreducible factors of f, a squarefree product of
+/// irreducible polynomials of degree d. A random a splits f through
+/// gcd(a^((p^d - 1) / 2) - 1, f) for odd p, where a^((p^d - 1) / 2) is the
+/// ((p - 1) / 2)-th power of the norm a a^p ... a^(p^(d - 1)), and through the
+/// trace a + a^2 + ... + a^(2^(d - 1)) for p = 2.
+pub fn equal_degree_factorization<F: IsPrimeField>(f: &Poly<F>, d: usize) -> Vec<Poly<F>> {
+    let f = monic(f);
+    assert!(d > 0 && f.degree().is_multiple_of(d), "degree is not a multiple of d");
+    let mut rng = rand::thread_rng();
+    let mut factors = vec![];
+    let mut pending = vec![f];
+    while let Some(f) = pending.pop() {
+        if f.degree() == d {
+            factors.push(f);
+            continue;
+        }
+        let x_p = frobenius(&f);
+        loop {
+            let a: Vec<FieldElement<F>> = (0..f.degree()).map(|_| random_element(&mut rng)).collect();
+            let a = Polynomial::new(&a);
+            let mut conjugate = a.clone();
+            let mut combined = a.clone();
+            for _ in 1..d {
+                conjugate = compose_mod(&conjugate, &x_p, &f);
+                combined = if is_characteristic_two::<F>() {
+                    combined + conjugate.clone()
+                } else {
+                    mul_mod(&combined, &conjugate, &f)
+                };
+            }
+            let b = if is_characteristic_two::<F>() {
+                combined
+            } else {
+                pow_mod(&combined, F::modulus_minus_one() >> 1, &f) - Polynomial::new(&[FieldElement::one()])
+            };
+            let g = gcd(&b, &f);
+            if g.degree() > 0 && g.degree() < f.degree() {
+                pending.push(div_rem(&f, &g).0);
+                pending.push(g);
+                break;
+            }
+        }
+    }
+    factors
+}
+
+/// Orders monic polynomials by degree, then by coefficients from the leading one.
+fn compare<F: IsPrimeField>(a: &Poly<F>, b: &Poly<F>) -> Ordering {
+    a.degree().cmp(&b.degree()).then_with(|| {
+        let representatives = |p: &Poly<F>| p.coefficients.iter().rev().map(|c| c.representative()).collect::<Vec<_>>();
+        representatives(a).cmp(&representatives(b))
+    })
+}
+
+/// Monic irreducible factors of f with their multiplicities, sorted by degree.
+/// The leading coefficient of f is dropped. Panics if f is zero.
+pub fn factor<F: IsPrimeField>(f: &Poly<F>) -> Vec<(Poly<F>, u32)> {
+    assert!(!Polynomial::new(&f.coefficients).coefficients.is_empty(), "cannot factor zero");
+    let mut factors = vec![];
+    for (squarefree, multiplicity) in squarefree_factorization(f) {
+        for (product, d) in distinct_degree_factorization(&squarefree) {
+            for irreducible in equal_degree_factorization(&product, d) {
+                factors.push((irreducible, multiplicity));
+            }
+        }
+    }
+    factors.sort_by(|(a, _), (b, _)| compare(a, b));
+    factors
+}
+
+/// Whether f is irreducible: it has degree n >= 1 and no factor of degree d <= n / 2,
+/// that is gcd(x^(p^d) - x, f) = 1 for all of them.
+pub fn is_irreducible<F: IsPrimeField>(f: &Poly<F>) -> bool {
+    let f = monic(f);
+    if f.coefficients.len() <= 1 {
+        return false;
+    }
+    let x_p = frobenius(&f);
+    let mut h = x_p.clone();
+    for _ in 1..=f.degree() / 2 {
+        if !is_one(&gcd(&(h.clone() - x()), &f)) {
+            return false;
+        }
+        h = compose_mod(&h, &x_p, &f);
+    }
+    true
+}
+
+/// Distinct roots of f in GF(p), in increasing order, from the linear factors of
+/// gcd(x^p - x, f). Panics if f is zero.
+pub fn roots<F: IsPrimeField>(f: &Poly<F>) -> Vec<FieldElement<F>> {
+    let f = monic(f);
+    assert!(!f.coefficients.is_empty(), "every element is a root of zero");
+    if f.degree() == 0 {
+        return vec![];
+    }
+    let linear_part = gcd(&(frobenius(&f) - x()), &f);
+    if linear_part.degree() == 0 {
+        return vec![];
+    }
+    let mut roots: Vec<FieldElement<F>> = equal_degree_factorization(&linear_part, 1)
+        .iter()
+        .map(|factor| -factor.coefficients[0].clone())
+        .collect();
+    roots.sort_by_key(|root| root.representative());
+    roots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lambdaworks_math::elliptic_curve::short_weierstrass::curves::bls12_381::default_types::{FrElement, FrField};
+    use lambdaworks_math::field::fields::u64_prime_field::{U64FieldElement, U64PrimeField};
+
+    type F17 = U64PrimeField<17>;
+    type F2 = U64PrimeField<2>;
+
+    fn poly<F: IsPrimeField>(coefficients: &[u64]) -> Poly<F> {
+        Polynomial::new(&coefficients.iter().map(|c| FieldElement::from(*c)).collect::<Vec<_>>())
+    }
+
+    /// Product of the factors raised to their multiplicities.
+    fn expand<F: IsPrimeField>(factors: &[(Poly<F>, u32)]) -> Poly<F> {
+        factors.iter().fold(poly(&[1]), |product, (factor, e)| {
+            (0..*e).fold(product, |product, _| product.mul_with_ref(factor))
+        })
+    }
+
+    /// All monic polynomials of the given degree over GF(p), with p^degree of them.
+    fn monic_polynomials(p: u64, degree: u32) -> impl Iterator<Item = Vec<u64>> {
+        (0..p.pow(degree)).map(move |mut index| {
+            let mut coefficients: Vec<u64> = (0..degree).map(|_| { let c = index % p; index /= p; c }).collect();
+            coefficients.push(1);
+            coefficients
+        })
+    }
+
+    #[test]
+    fn gcd_and_division() {
+        // (x + 1)(x + 2) and (x + 1)(x + 3) over GF(17)
+        let a = poly::<F17>(&[2, 3, 1]);
+        let b = poly::<F17>(&[3, 4, 1]);
+        assert_eq!(gcd(&a, &b), poly(&[1, 1]));
+        assert_eq!(gcd(&a.scale_coeffs(&U64FieldElement::from(5)), &Polynomial::zero()), a);
+        assert_eq!(gcd::<F17>(&Polynomial::zero(), &Polynomial::zero()), Polynomial::zero());
+        let (q, r) = div_rem(&a, &b);
+        // a - b = -x - 1
+        assert_eq!((q, r), (poly(&[1]), poly(&[16, 16])));
+    }
+
+    #[test]
+    fn composition_and_powers() {
+        let f = poly::<F17>(&[3, 0, 5, 1, 1]);
+        let g = poly::<F17>(&[1, 2, 3]);
+        let h = poly::<F17>(&[0, 4, 0, 7]);
+        // g(h) = 1 + 2 h + 3 h^2
+        let expected = rem(&(poly::<F17>(&[1]) + h.scale_coeffs(&FieldElement::from(2)) + h.mul_with_ref(&h).scale_coeffs(&FieldElement::from(3))), &f);
+        assert_eq!(compose_mod(&g, &h, &f), expected);
+        // x^(17^2) by powers and by composition agree
+        let x_p = frobenius(&f);
+        assert_eq!(compose_mod(&x_p, &x_p, &f), pow_mod(&x(), 17_u64 * 17, &f));
+        // Fermat: h^17 = h(x^17)
+        assert_eq!(pow_mod(&h, 17_u64, &f), compose_mod(&h, &x_p, &f));
+    }
+
+    #[test]
+    fn irreducible_counts() {
+        // Gauss's formula (1 / n) sum mu(d) p^(n / d) for the monic irreducibles of degree n
+        let count = |p: u64, degree: u32| -> usize {
+            monic_polynomials(p, degree).filter(|c| match p {
+                2 => is_irreducible(&poly::<F2>(c)),
+                _ => is_irreducible(&poly::<F17>(c)),
+            }).count()
+        };
+        assert_eq!(count(2, 3), 2);
+        assert_eq!(count(2, 4), 3);
+        assert_eq!(count(2, 6), 9);
+        assert_eq!(count(17, 2), (17 * 17 - 17) / 2);
+        assert_eq!(count(17, 3), (17 * 17 * 17 - 17) / 3);
+    }
+
+    #[test]
+    fn factorizations_multiply_back() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..30 {
+            let degree = rng.gen_range(1..12);
+            let coefficients: Vec<u64> = (0..=degree).map(|_| rng.gen_range(0..17)).collect();
+            let f = poly::<F17>(&coefficients);
+            if f.coefficients.is_empty() {
+                continue;
+            }
+            let factors = factor(&f);
+            assert_eq!(expand(&factors), monic(&f));
+            assert!(factors.iter().all(|(g, _)| is_irreducible(g)));
+        }
+        // x^17 - x splits into all the linear factors
+        let mut coefficients = vec![0; 18];
+        (coefficients[1], coefficients[17]) = (16, 1);
+        let factors = factor(&poly::<F17>(&coefficients));
+        assert_eq!(factors, (0..17).map(|a| (poly(&[a, 1]), 1)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn repeated_factors_in_characteristic_two() {
+        // (x^2 + x + 1)^2 (x + 1)^3 x^4 over GF(2), with a p-th power part
+        let a = poly::<F2>(&[1, 1, 1]);
+        let b = poly::<F2>(&[1, 1]);
+        let expected = vec![(poly(&[0, 1]), 4), (b.clone(), 3), (a.clone(), 2)];
+        let f = expand(&expected);
+        assert_eq!(factor(&f), expected);
+        // x^6 + x^4 + x^2 + 1 = (x^3 + x^2 + x + 1)^2 = (x + 1)^6
+        assert_eq!(factor(&poly::<F2>(&[1, 0, 1, 0, 1, 0, 1])), vec![(b, 6)]);
+    }
+
+    #[test]
+    fn roots_in_small_and_large_fields() {
+        // (x - 3)^2 (x - 5)(x^2 + 1), where x^2 + 1 splits since 4^2 = -1
+        let f = expand::<F17>(&[(poly(&[14, 1]), 2), (poly(&[12, 1]), 1), (poly(&[1, 0, 1]), 1)]);
+        let roots: Vec<u64> = roots(&f).iter().map(|r| r.representative()).collect();
+        assert_eq!(roots, [3, 4, 5, 13]);
+        assert!(super::roots(&poly::<F17>(&[3, 0, 1])).is_empty());
+
+        // BLS12-381 scalar field: (x - a)(x - b)(x^2 - n) with a non-residue n
+        let (a, b) = (FrElement::from(0x243f6a8885a308d3), FrElement::from(0x13198a2e03707344));
+        let non_residue = FrElement::from(7);
+        assert!(non_residue.sqrt().is_none());
+        let f = Polynomial::new(&[-&a, FrElement::one()])
+            .mul_with_ref(&Polynomial::new(&[-&b, FrElement::one()]))
+            .mul_with_ref(&Polynomial::new(&[-non_residue, FrElement::zero(), FrElement::one()]));
+        let mut expected = vec![a, b];
+        expected.sort_by_key(|r| r.representative());
+        assert_eq!(super::roots::<FrField>(&f), expected);
+        let factors = factor(&f);
+        assert_eq!(factors.iter().map(|(g, e)| (g.degree(), *e)).collect::<Vec<_>>(), [(1, 1), (1, 1), (2, 1)]);
+    }
+}
@@ -0,0 +1,188 @@
+use lambdaworks_math::unsigned_integer::element::UnsignedInteger;
+use rand::prelude::*;
+
+use crate::lucas::strong_lucas_round;
+use crate::{rem_u64, sub_mod, MontgomeryContext};
+
+/// Primes below 100, used for trial division before the probabilistic tests.
+const SMALL_PRIMES: [u64; 25] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+];
+
+/// Integers below 101^2 without prime factors below 100 are prime.
+const TRIAL_DIVISION_BOUND: u64 = 101 * 101;
+
+/// Decides primality by trial division by SMALL_PRIMES, when possible.
+pub(crate) fn trial_division<const NUM_LIMBS: usize>(n: &UnsignedInteger<NUM_LIMBS>) -> Option<bool> {
+    let bound = UnsignedInteger::<NUM_LIMBS>::from_u64(TRIAL_DIVISION_BOUND);
+    if *n < UnsignedInteger::from_u64(2) {
+        return Some(false);
+    }
+    for p in SMALL_PRIMES {
+        if rem_u64(n, p) == 0 {
+            return Some(*n == UnsignedInteger::from_u64(p));
+        }
+    }
+    if *n < bound {
+        return Some(true);
+    }
+    None
+}
+
+/// Writes m = d * 2^s with d odd and returns (d, s). `m` must be nonzero.
+pub(crate) fn odd_part<const NUM_LIMBS: usize>(m: &UnsignedInteger<NUM_LIMBS>) -> (UnsignedInteger<NUM_LIMBS>, usize) {
+    let mut d = *m;
+    let mut s = 0;
+    while d.limbs[NUM_LIMBS - 1] & 1 == 0 {
+        d >>= 1;
+        s += 1;
+    }
+    (d, s)
+}
+
+/// Miller-Rabin round for odd n with n - 1 = d * 2^s, base in Montgomery form.
+fn miller_rabin_round<const NUM_LIMBS: usize>(
+        ctx: &MontgomeryContext<NUM_LIMBS>,
+        d: &UnsignedInteger<NUM_LIMBS>,
+        s: usize,
+        base: &UnsignedInteger<NUM_LIMBS>,
+    ) -> bool {
+    let one = ctx.one();
+    let minus_one = sub_mod(&UnsignedInteger::from_u64(0), &one, ctx.modulus());
+
+    let mut x = ctx.pow(base, d);
+    if x == one || x == minus_one {
+        return true;
+    }
+    for _ in 1..s {
+        x = ctx.square(&x);
+        if x == minus_one {
+            return true;
+        }
+        if x == one {
+            return false;
+        }
+    }
+    false
+}
+
+/// Returns true if the odd integer n > 2 is a strong probable prime to `base`.
+/// Returns false for n < 3.
+pub fn is_strong_probable_prime<const NUM_LIMBS: usize>(
+        n: &UnsignedInteger<NUM_LIMBS>,
+        base: &UnsignedInteger<NUM_LIMBS>,
+    ) -> bool {
+    if *n < UnsignedInteger::from_u64(3) {
+        return false;
+    }
+    let Some(ctx) = MontgomeryContext::new(n) else {
+        return false;
+    };
+    let (d, s) = odd_part(&(n - UnsignedInteger::from_u64(1)));
+    miller_rabin_round(&ctx, &d, s, &ctx.to_mont(base))
+}
+
+/// Miller-Rabin test with `rounds` random bases. A composite passes with
+/// probability at most 4^(-rounds).
+pub fn is_probable_prime<const NUM_LIMBS: usize>(n: &UnsignedInteger<NUM_LIMBS>, rounds: usize) -> bool {
+    if let Some(is_prime) = trial_division(n) {
+        return is_prime;
+    }
+    let ctx = MontgomeryContext::new(n).unwrap();
+    let (d, s) = odd_part(&(n - UnsignedInteger::from_u64(1)));
+
+    // bases are taken uniformly in [2, n - 2]
+    let two = UnsignedInteger::<NUM_LIMBS>::from_u64(2);
+    let range = n - UnsignedInteger::from_u64(3);
+    let mut rng = rand::thread_rng();
+    (0..rounds).all(|_| {
+        let mut limbs = [0_u64; NUM_LIMBS];
+        rng.fill(&mut limbs[..]);
+        let (_, base) = UnsignedInteger::from_limbs(limbs).div_rem(&range);
+        miller_rabin_round(&ctx, &d, s, &ctx.to_mont(&(base + two)))
+    })
+}
+
+/// Baillie-PSW test: Miller-Rabin to base 2 followed by a strong Lucas test.
+/// No composite is known to pass it, and none exists below 2^64, where the
+/// answer is therefore exact.
+pub fn is_prime_bpsw<const NUM_LIMBS: usize>(n: &UnsignedInteger<NUM_LIMBS>) -> bool {
+    if let Some(is_prime) = trial_division(n) {
+        return is_prime;
+    }
+    let ctx = MontgomeryContext::new(n).unwrap();
+    let (d, s) = odd_part(&(n - UnsignedInteger::from_u64(1)));
+    miller_rabin_round(&ctx, &d, s, &ctx.to_mont(&UnsignedInteger::from_u64(2)))
+        && strong_lucas_round(&ctx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sieve(bound: usize) -> Vec<bool> {
+        let mut is_prime = vec![true; bound];
+        is_prime[0] = false;
+        is_prime[1] = false;
+        for i in 2..bound {
+            if is_prime[i] {
+                for j in (i * i..bound).step_by(i) {
+                    is_prime[j] = false;
+                }
+            }
+        }
+        is_prime
+    }
+
+    #[test]
+    fn primality_matches_sieve() {
+        for (n, is_prime) in sieve(30_000).into_iter().enumerate() {
+            let n = UnsignedInteger::<1>::from_u64(n as u64);
+            assert_eq!(is_prime_bpsw(&n), is_prime);
+            assert_eq!(is_probable_prime(&n, 5), is_prime);
+        }
+    }
+
+    #[test]
+    fn carmichael_numbers_are_composite() {
+        for n in [561, 1105, 1729, 2465, 2821, 6601, 8911, 41041, 825265, 321197185] {
+            let n = UnsignedInteger::<2>::from_u64(n);
+            assert!(!is_probable_prime(&n, 10));
+            assert!(!is_prime_bpsw(&n));
+        }
+    }
+
+    #[test]
+    fn strong_pseudoprimes_fail_bpsw() {
+        // strong pseudoprimes to base 2
+        let two = UnsignedInteger::<2>::from_u64(2);
+        for n in [2047, 3277, 4033, 4681, 8321, 3215031751, 2152302898747] {
+            let n = UnsignedInteger::<2>::from_u64(n);
+            assert!(is_strong_probable_prime(&n, &two));
+            assert!(!is_prime_bpsw(&n));
+        }
+        // squares of primes
+        let n = UnsignedInteger::<2>::from_u64(10007 * 10007);
+        assert!(!is_prime_bpsw(&n));
+        // n = 1 has n - 1 = 0, which has no odd part
+        assert!(!is_strong_probable_prime(&UnsignedInteger::<2>::from_u64(1), &two));
+        assert!(!is_strong_probable_prime(&UnsignedInteger::<2>::from_u64(0), &two));
+    }
+
+    #[test]
+    fn large_primes_and_semiprimes() {
+        let bls12_381_r = UnsignedInteger::<4>::from_hex_unchecked(
+            "73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001"
+        );
+        assert!(is_prime_bpsw(&bls12_381_r));
+        assert!(is_probable_prime(&bls12_381_r, 20));
+
+        let mersenne_127 = UnsignedInteger::<2>::from_u128((1 << 127) - 1);
+        assert!(is_prime_bpsw(&mersenne_127));
+        assert!(is_probable_prime(&mersenne_127, 20));
+
+        let semiprime = UnsignedInteger::<2>::from_u128(0xffff_ffff_ffff_ffc5 * 0xffff_ffff_ffff_ff43);
+        assert!(!is_prime_bpsw(&semiprime));
+        assert!(!is_probable_prime(&semiprime, 20));
+    }
+}
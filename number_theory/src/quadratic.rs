@@ -0,0 +1,386 @@
+use lambdaworks_math::unsigned_integer::element::UnsignedInteger;
+
+use crate::{add_mod, bit_at, is_prime_bpsw, isqrt, sub_mod, MontgomeryContext};
+
+/// Jacobi symbol (a / n) for odd n, by the binary reciprocity algorithm:
+/// only shifts, subtractions and the rules for (2 / n) and reciprocity are used.
+/// Panics if n is even.
+pub fn jacobi<const NUM_LIMBS: usize>(a: &UnsignedInteger<NUM_LIMBS>, n: &UnsignedInteger<NUM_LIMBS>) -> i8 {
+    let zero = UnsignedInteger::<NUM_LIMBS>::from_u64(0);
+    let one = UnsignedInteger::<NUM_LIMBS>::from_u64(1);
+    assert!(n.limbs[NUM_LIMBS - 1] & 1 == 1, "the Jacobi symbol needs an odd n");
+
+    let (_, mut a) = a.div_rem(n);
+    let mut n = *n;
+    let mut result = 1;
+    while a != zero {
+        while a.limbs[NUM_LIMBS - 1] & 1 == 0 {
+            a >>= 1;
+            // (2 / n) = -1 iff n = 3, 5 (mod 8)
+            if matches!(n.limbs[NUM_LIMBS - 1] & 7, 3 | 5) {
+                result = -result;
+            }
+        }
+        // both are odd now: apply reciprocity to get a >= n
+        if a < n {
+            (a, n) = (n, a);
+            if a.limbs[NUM_LIMBS - 1] & 3 == 3 && n.limbs[NUM_LIMBS - 1] & 3 == 3 {
+                result = -result;
+            }
+        }
+        // (a / n) = (a - n / n), and a - n is even
+        a = a - n;
+    }
+    if n == one {
+        result
+    } else {
+        0
+    }
+}
+
+/// Legendre symbol (a / p) for an odd prime p: 1 if a is a nonzero square
+/// modulo p, -1 if it is not a square and 0 if p divides a.
+pub fn legendre<const NUM_LIMBS: usize>(a: &UnsignedInteger<NUM_LIMBS>, p: &UnsignedInteger<NUM_LIMBS>) -> i8 {
+    jacobi(a, p)
+}
+
+/// Returns the two roots ordered as (r, p - r), with r <= p - r.
+fn ordered_roots<const NUM_LIMBS: usize>(
+        r: UnsignedInteger<NUM_LIMBS>,
+        p: &UnsignedInteger<NUM_LIMBS>,
+    ) -> (UnsignedInteger<NUM_LIMBS>, UnsignedInteger<NUM_LIMBS>) {
+    let other = sub_mod(&UnsignedInteger::from_u64(0), &r, p);
+    if r <= other {
+        (r, other)
+    } else {
+        (other, r)
+    }
+}
+
+/// Handles the cases of `sqrt_mod` that do not need an odd prime:
+/// p = 2, a = 0 (mod p) and non-residues.
+fn sqrt_mod_trivial<const NUM_LIMBS: usize>(
+        a: &UnsignedInteger<NUM_LIMBS>,
+        p: &UnsignedInteger<NUM_LIMBS>,
+    ) -> Option<Option<(UnsignedInteger<NUM_LIMBS>, UnsignedInteger<NUM_LIMBS>)>> {
+    let (_, a) = a.div_rem(p);
+    if *p == UnsignedInteger::from_u64(2) {
+        return Some(Some((a, a)));
+    }
+    match legendre(&a, p) {
+        0 => Some(Some((a, a))),
+        -1 => Some(None),
+        _ => None,
+    }
+}
+
+/// Square roots of `a` modulo a prime `p`, as (r, p - r) with r <= p - r,
+/// or `None` if `a` is not a square.
+/// Uses a single exponentiation when p = 3 (mod 4) or p = 5 (mod 8), and
+/// Tonelli-Shanks otherwise.
+pub fn sqrt_mod<const NUM_LIMBS: usize>(
+        a: &UnsignedInteger<NUM_LIMBS>,
+        p: &UnsignedInteger<NUM_LIMBS>,
+    ) -> Option<(UnsignedInteger<NUM_LIMBS>, UnsignedInteger<NUM_LIMBS>)> {
+    if let Some(roots) = sqrt_mod_trivial(a, p) {
+        return roots;
+    }
+    let ctx = MontgomeryContext::new(p).expect("sqrt_mod needs a prime modulus");
+    let one = ctx.one();
+    let a_mont = ctx.to_mont(a);
+
+    let root = match p.limbs[NUM_LIMBS - 1] & 7 {
+        3 | 7 => {
+            // r = a^((p + 1) / 4)
+            let exp = (*p >> 2) + UnsignedInteger::from_u64(1);
+            ctx.pow(&a_mont, &exp)
+        }
+        5 => {
+            // Atkin: b = (2a)^((p - 5) / 8), i = 2a b^2 and r = a b (i - 1)
+            let two_a = add_mod(&a_mont, &a_mont, p);
+            let b = ctx.pow(&two_a, &(*p >> 3));
+            let i = ctx.mul(&two_a, &ctx.square(&b));
+            ctx.mul(&ctx.mul(&a_mont, &b), &sub_mod(&i, &one, p))
+        }
+        _ => {
+            // Tonelli-Shanks, with p - 1 = q * 2^s and q odd
+            let mut s = 0;
+            let mut q = *p - UnsignedInteger::from_u64(1);
+            while q.limbs[NUM_LIMBS - 1] & 1 == 0 {
+                q >>= 1;
+                s += 1;
+            }
+            let mut z = UnsignedInteger::<NUM_LIMBS>::from_u64(2);
+            while legendre(&z, p) != -1 {
+                z = z + UnsignedInteger::from_u64(1);
+            }
+
+            let mut m = s;
+            let mut c = ctx.pow(&ctx.to_mont(&z), &q);
+            let mut t = ctx.pow(&a_mont, &q);
+            let mut r = ctx.pow(&a_mont, &((q >> 1) + UnsignedInteger::from_u64(1)));
+            // Invariant: r^2 = a t, with t of order 2^i < 2^m and c of order 2^m.
+            while t != one {
+                let mut i = 0;
+                let mut t_pow = t;
+                while t_pow != one {
+                    t_pow = ctx.square(&t_pow);
+                    i += 1;
+                }
+                let mut b = c;
+                for _ in 0..m - i - 1 {
+                    b = ctx.square(&b);
+                }
+                m = i;
+                c = ctx.square(&b);
+                t = ctx.mul(&t, &c);
+                r = ctx.mul(&r, &b);
+            }
+            r
+        }
+    };
+    Some(ordered_roots(ctx.from_mont(&root), p))
+}
+
+/// Same as `sqrt_mod`, by Cipolla's algorithm: if w = t^2 - a is not a square,
+/// then (t + sqrt(w))^((p + 1) / 2) in GF(p^2) = GF(p)[sqrt(w)] is a root of a.
+pub fn sqrt_mod_cipolla<const NUM_LIMBS: usize>(
+        a: &UnsignedInteger<NUM_LIMBS>,
+        p: &UnsignedInteger<NUM_LIMBS>,
+    ) -> Option<(UnsignedInteger<NUM_LIMBS>, UnsignedInteger<NUM_LIMBS>)> {
+    if let Some(roots) = sqrt_mod_trivial(a, p) {
+        return roots;
+    }
+    let ctx = MontgomeryContext::new(p).expect("sqrt_mod_cipolla needs a prime modulus");
+    let a_mont = ctx.to_mont(a);
+
+    let mut t = UnsignedInteger::<NUM_LIMBS>::from_u64(1);
+    let w = loop {
+        let t_mont = ctx.to_mont(&t);
+        let w = sub_mod(&ctx.square(&t_mont), &a_mont, p);
+        if legendre(&ctx.from_mont(&w), p) == -1 {
+            break w;
+        }
+        t = t + UnsignedInteger::from_u64(1);
+    };
+
+    // (x1 + y1 sqrt(w)) (x2 + y2 sqrt(w)) = (x1 x2 + y1 y2 w) + (x1 y2 + x2 y1) sqrt(w)
+    let mul = |(x1, y1): (UnsignedInteger<NUM_LIMBS>, UnsignedInteger<NUM_LIMBS>),
+               (x2, y2): (UnsignedInteger<NUM_LIMBS>, UnsignedInteger<NUM_LIMBS>)| {
+        (
+            add_mod(&ctx.mul(&x1, &x2), &ctx.mul(&ctx.mul(&y1, &y2), &w), p),
+            add_mod(&ctx.mul(&x1, &y2), &ctx.mul(&x2, &y1), p),
+        )
+    };
+
+    // (p + 1) / 2, without overflowing
+    let exp = (*p >> 1) + UnsignedInteger::from_u64(1);
+    let mut base = (ctx.to_mont(&t), ctx.one());
+    let mut result = (ctx.one(), UnsignedInteger::from_u64(0));
+    for i in 0..exp.bits_le() {
+        if bit_at(&exp, i) {
+            result = mul(result, base);
+        }
+        base = mul(base, base);
+    }
+    Some(ordered_roots(ctx.from_mont(&result.0), p))
+}
+
+/// Cornacchia's algorithm: a solution of x^2 + d y^2 = m for a prime m and
+/// 0 < d < m, or `None` if there is none. Euclid's algorithm on m and a square
+/// root r of -d modulo m stops at the first remainder x < sqrt(m), which is the
+/// only candidate; then (m - x^2) / d must be a square y^2.
+pub fn cornacchia<const NUM_LIMBS: usize>(
+        d: &UnsignedInteger<NUM_LIMBS>,
+        m: &UnsignedInteger<NUM_LIMBS>,
+    ) -> Option<(UnsignedInteger<NUM_LIMBS>, UnsignedInteger<NUM_LIMBS>)> {
+    let zero = UnsignedInteger::<NUM_LIMBS>::from_u64(0);
+    if *d == zero || d >= m {
+        return None;
+    }
+    let (r, _) = sqrt_mod(&(*m - *d), m)?;
+
+    // m is prime, so not a square, and x < sqrt(m) means x <= isqrt(m)
+    let bound = isqrt(m);
+    let (mut a, mut b) = (*m, r);
+    while b > bound {
+        (a, b) = (b, a.div_rem(&b).1);
+    }
+    let x = b;
+    let (y_squared, remainder) = (*m - x * x).div_rem(d);
+    let y = isqrt(&y_squared);
+    (remainder == zero && y * y == y_squared).then_some((x, y))
+}
+
+/// Writes n = x^2 + y^2 with x >= y, for n = 2 or a prime n = 1 (mod 4),
+/// which are exactly the primes that are sums of two squares.
+/// Returns `None` for other n, and if the result fails the check x^2 + y^2 = n.
+pub fn sum_of_two_squares<const NUM_LIMBS: usize>(
+        n: &UnsignedInteger<NUM_LIMBS>,
+    ) -> Option<(UnsignedInteger<NUM_LIMBS>, UnsignedInteger<NUM_LIMBS>)> {
+    let one = UnsignedInteger::<NUM_LIMBS>::from_u64(1);
+    if *n == UnsignedInteger::from_u64(2) {
+        return Some((one, one));
+    }
+    if n.limbs[NUM_LIMBS - 1] & 3 != 1 || !is_prime_bpsw(n) {
+        return None;
+    }
+    let (x, y) = cornacchia(&one, n)?;
+    // x, y <= sqrt(n), so the check cannot overflow
+    (x * x + y * y == *n).then_some((x.max(y), x.min(y)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mul_mod;
+
+    // Legendre symbol by listing the squares modulo p.
+    fn legendre_by_squares(a: u64, p: u64) -> i8 {
+        if a.is_multiple_of(p) {
+            0
+        } else if (1..p).any(|x| x * x % p == a % p) {
+            1
+        } else {
+            -1
+        }
+    }
+
+    #[test]
+    fn legendre_modulo_17() {
+        // squares modulo 17 are 1, 2, 4, 8, 9, 13, 15, 16
+        let p = UnsignedInteger::<1>::from_u64(17);
+        let symbols: Vec<i8> = (0..17).map(|a| legendre(&UnsignedInteger::from_u64(a), &p)).collect();
+        assert_eq!(symbols, [0, 1, 1, -1, 1, -1, -1, -1, 1, 1, -1, -1, -1, 1, -1, 1, 1]);
+    }
+
+    #[test]
+    fn legendre_matches_squares_for_small_primes() {
+        for p in [3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 97, 101, 257] {
+            for a in 0..2 * p {
+                let symbol = legendre(&UnsignedInteger::<2>::from_u64(a), &UnsignedInteger::from_u64(p));
+                assert_eq!(symbol, legendre_by_squares(a, p));
+            }
+        }
+    }
+
+    #[test]
+    fn jacobi_is_multiplicative_in_n() {
+        // (a / p1 p2 ... pk) = (a / p1) (a / p2) ... (a / pk)
+        let odd_primes = [3_u64, 5, 7, 11, 13];
+        let factor = |mut n: u64| {
+            let mut factors = vec![];
+            for p in odd_primes {
+                while n.is_multiple_of(p) {
+                    n /= p;
+                    factors.push(p);
+                }
+            }
+            (n == 1).then_some(factors)
+        };
+        for n in (1..400_u64).step_by(2) {
+            let Some(factors) = factor(n) else {
+                continue;
+            };
+            for a in 0..n {
+                let expected: i8 = factors.iter().map(|p| legendre_by_squares(a, *p)).product();
+                let symbol = jacobi(&UnsignedInteger::<1>::from_u64(a), &UnsignedInteger::from_u64(n));
+                assert_eq!(symbol, expected);
+            }
+        }
+    }
+
+    fn check_roots<const NUM_LIMBS: usize>(
+            a: &UnsignedInteger<NUM_LIMBS>,
+            p: &UnsignedInteger<NUM_LIMBS>,
+        ) {
+        let roots = sqrt_mod(a, p);
+        assert_eq!(roots, sqrt_mod_cipolla(a, p));
+        let (_, a) = a.div_rem(p);
+        match roots {
+            Some((r1, r2)) => {
+                assert!(r1 <= r2);
+                assert_eq!(mul_mod(&r1, &r1, p), a);
+                assert_eq!(mul_mod(&r2, &r2, p), a);
+            }
+            None => assert_eq!(legendre(&a, p), -1),
+        }
+    }
+
+    #[test]
+    fn sqrt_mod_small_primes() {
+        // 17 = 1 (mod 16) needs Tonelli-Shanks, 13 = 5 (mod 8), 11 = 3 (mod 4)
+        for p in [2_u64, 3, 5, 7, 11, 13, 17, 41, 73, 97, 113, 193, 257, 65537] {
+            for a in 0..p.min(300) {
+                check_roots(&UnsignedInteger::<1>::from_u64(a), &UnsignedInteger::from_u64(p));
+            }
+        }
+        let p = UnsignedInteger::<1>::from_u64(17);
+        let roots = sqrt_mod(&UnsignedInteger::from_u64(2), &p);
+        assert_eq!(roots, Some((UnsignedInteger::from_u64(6), UnsignedInteger::from_u64(11))));
+        assert_eq!(sqrt_mod(&UnsignedInteger::from_u64(3), &p), None);
+    }
+
+    #[test]
+    fn sqrt_mod_large_primes() {
+        let bls12_381_r = UnsignedInteger::<4>::from_hex_unchecked(
+            "73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001"
+        );
+        let bn254_r = UnsignedInteger::<4>::from_hex_unchecked(
+            "30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001"
+        );
+        // 2^255 - 19 = 5 (mod 8)
+        let p25519 = UnsignedInteger::<4>::from_hex_unchecked(
+            "7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffed"
+        );
+        // 2^127 - 1 = 3 (mod 4)
+        let mersenne_127 = UnsignedInteger::<4>::from_u128((1 << 127) - 1);
+        let a = UnsignedInteger::<4>::from_hex_unchecked(
+            "e4840ac57f86f5e293b1d67bc8de5d9a12a70a615d0b8e4d2fc5e69ac5db47f"
+        );
+        for p in [bls12_381_r, bn254_r, p25519, mersenne_127] {
+            for k in 0..4_u64 {
+                let x = a + UnsignedInteger::from_u64(k);
+                check_roots(&x, &p);
+                check_roots(&mul_mod(&x, &x, &p), &p);
+            }
+        }
+    }
+
+    #[test]
+    fn cornacchia_matches_brute_force() {
+        let primes = (3..400_u64).filter(|&n| (2..n).take_while(|d| d * d <= n).all(|d| n % d != 0));
+        for m in primes {
+            for d in 1..m.min(12) {
+                let exists = (0..m).take_while(|y| d * y * y <= m).any(|y| {
+                    let x = isqrt(&UnsignedInteger::<1>::from_u64(m - d * y * y)).limbs[0];
+                    x * x + d * y * y == m
+                });
+                match cornacchia(&UnsignedInteger::<1>::from_u64(d), &UnsignedInteger::from_u64(m)) {
+                    Some((x, y)) => assert_eq!(x.limbs[0].pow(2) + d * y.limbs[0].pow(2), m),
+                    None => assert!(!exists, "x^2 + {d} y^2 = {m}"),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn sums_of_two_squares() {
+        let two_squares = |n: u64| sum_of_two_squares(&UnsignedInteger::<1>::from_u64(n)).map(|(x, y)| (x.limbs[0], y.limbs[0]));
+        assert_eq!(two_squares(2), Some((1, 1)));
+        assert_eq!(two_squares(5), Some((2, 1)));
+        assert_eq!(two_squares(13), Some((3, 2)));
+        assert_eq!(two_squares(1_000_000_009), Some((31400, 3747)));
+        // 3 mod 4, composite, and 1 mod 4 but composite
+        assert_eq!(two_squares(7), None);
+        assert_eq!(two_squares(25), None);
+        assert_eq!(two_squares(1), None);
+
+        // the BLS12-381 scalar field order is 1 (mod 4)
+        let r = UnsignedInteger::<4>::from_hex_unchecked(
+            "73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001"
+        );
+        let (x, y) = sum_of_two_squares(&r).unwrap();
+        assert_eq!(x * x + y * y, r);
+    }
+}
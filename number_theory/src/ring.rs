@@ -0,0 +1,205 @@
+use lambdaworks_math::unsigned_integer::element::UnsignedInteger;
+
+use crate::{add_mod, sub_mod, window::sliding_window_pow, BarrettContext, MontgomeryContext};
+
+/// Arithmetic modulo a runtime modulus m through a precomputed reducer.
+/// Elements live in an internal representation: use `to_repr` and `to_standard`
+/// to convert from and to standard form, or `mul_mod` and `pow_mod` directly.
+/// Addition and subtraction work in any representation that is linear in a,
+/// such as a * R mod m.
+pub trait ModularRing<const NUM_LIMBS: usize> {
+    fn modulus(&self) -> &UnsignedInteger<NUM_LIMBS>;
+
+    /// Representation of 1.
+    fn one(&self) -> UnsignedInteger<NUM_LIMBS>;
+
+    /// Representation of a, for any a.
+    fn to_repr(&self, a: &UnsignedInteger<NUM_LIMBS>) -> UnsignedInteger<NUM_LIMBS>;
+
+    /// Standard form of a represented element.
+    fn to_standard(&self, a: &UnsignedInteger<NUM_LIMBS>) -> UnsignedInteger<NUM_LIMBS>;
+
+    /// Product of two represented elements.
+    fn mul(&self, a: &UnsignedInteger<NUM_LIMBS>, b: &UnsignedInteger<NUM_LIMBS>) -> UnsignedInteger<NUM_LIMBS>;
+
+    fn square(&self, a: &UnsignedInteger<NUM_LIMBS>) -> UnsignedInteger<NUM_LIMBS> {
+        self.mul(a, a)
+    }
+
+    fn add(&self, a: &UnsignedInteger<NUM_LIMBS>, b: &UnsignedInteger<NUM_LIMBS>) -> UnsignedInteger<NUM_LIMBS> {
+        add_mod(a, b, self.modulus())
+    }
+
+    fn sub(&self, a: &UnsignedInteger<NUM_LIMBS>, b: &UnsignedInteger<NUM_LIMBS>) -> UnsignedInteger<NUM_LIMBS> {
+        sub_mod(a, b, self.modulus())
+    }
+
+    /// Raises a represented element to `exp`, with a sliding window of 4 bits.
+    fn pow(&self, base: &UnsignedInteger<NUM_LIMBS>, exp: &UnsignedInteger<NUM_LIMBS>) -> UnsignedInteger<NUM_LIMBS> {
+        sliding_window_pow::<_, 4, NUM_LIMBS>(base, exp, &self.one(), |a, b| self.mul(a, b))
+    }
+
+    /// Product a * b mod m of integers in standard form.
+    fn mul_mod(&self, a: &UnsignedInteger<NUM_LIMBS>, b: &UnsignedInteger<NUM_LIMBS>) -> UnsignedInteger<NUM_LIMBS> {
+        self.to_standard(&self.mul(&self.to_repr(a), &self.to_repr(b)))
+    }
+
+    /// base^exp mod m of an integer in standard form.
+    fn pow_mod(&self, base: &UnsignedInteger<NUM_LIMBS>, exp: &UnsignedInteger<NUM_LIMBS>) -> UnsignedInteger<NUM_LIMBS> {
+        self.to_standard(&self.pow(&self.to_repr(base), exp))
+    }
+}
+
+/// Montgomery form a * R mod m.
+impl<const NUM_LIMBS: usize> ModularRing<NUM_LIMBS> for MontgomeryContext<NUM_LIMBS> {
+    fn modulus(&self) -> &UnsignedInteger<NUM_LIMBS> {
+        self.modulus()
+    }
+
+    fn one(&self) -> UnsignedInteger<NUM_LIMBS> {
+        self.one()
+    }
+
+    fn to_repr(&self, a: &UnsignedInteger<NUM_LIMBS>) -> UnsignedInteger<NUM_LIMBS> {
+        self.to_mont(a)
+    }
+
+    fn to_standard(&self, a: &UnsignedInteger<NUM_LIMBS>) -> UnsignedInteger<NUM_LIMBS> {
+        self.from_mont(a)
+    }
+
+    fn mul(&self, a: &UnsignedInteger<NUM_LIMBS>, b: &UnsignedInteger<NUM_LIMBS>) -> UnsignedInteger<NUM_LIMBS> {
+        self.mul(a, b)
+    }
+
+    fn mul_mod(&self, a: &UnsignedInteger<NUM_LIMBS>, b: &UnsignedInteger<NUM_LIMBS>) -> UnsignedInteger<NUM_LIMBS> {
+        self.mul_mod(a, b)
+    }
+}
+
+/// Standard form, reduced below m.
+impl<const NUM_LIMBS: usize> ModularRing<NUM_LIMBS> for BarrettContext<NUM_LIMBS> {
+    fn modulus(&self) -> &UnsignedInteger<NUM_LIMBS> {
+        self.modulus()
+    }
+
+    fn one(&self) -> UnsignedInteger<NUM_LIMBS> {
+        self.to_repr(&UnsignedInteger::from_u64(1))
+    }
+
+    fn to_repr(&self, a: &UnsignedInteger<NUM_LIMBS>) -> UnsignedInteger<NUM_LIMBS> {
+        a.div_rem(self.modulus()).1
+    }
+
+    fn to_standard(&self, a: &UnsignedInteger<NUM_LIMBS>) -> UnsignedInteger<NUM_LIMBS> {
+        *a
+    }
+
+    fn mul(&self, a: &UnsignedInteger<NUM_LIMBS>, b: &UnsignedInteger<NUM_LIMBS>) -> UnsignedInteger<NUM_LIMBS> {
+        self.mul_mod(a, b)
+    }
+}
+
+/// The fastest reducer valid for a modulus: Montgomery for odd moduli, Barrett otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reducer<const NUM_LIMBS: usize> {
+    Montgomery(MontgomeryContext<NUM_LIMBS>),
+    Barrett(BarrettContext<NUM_LIMBS>),
+}
+
+impl<const NUM_LIMBS: usize> Reducer<NUM_LIMBS> {
+    /// Returns `None` if the modulus is zero, or even and too wide for `BarrettContext`.
+    pub fn new(modulus: &UnsignedInteger<NUM_LIMBS>) -> Option<Self> {
+        match MontgomeryContext::new(modulus) {
+            Some(ctx) => Some(Self::Montgomery(ctx)),
+            None => BarrettContext::new(modulus).map(Self::Barrett),
+        }
+    }
+}
+
+impl<const NUM_LIMBS: usize> ModularRing<NUM_LIMBS> for Reducer<NUM_LIMBS> {
+    fn modulus(&self) -> &UnsignedInteger<NUM_LIMBS> {
+        match self {
+            Self::Montgomery(ctx) => ModularRing::modulus(ctx),
+            Self::Barrett(ctx) => ModularRing::modulus(ctx),
+        }
+    }
+
+    fn one(&self) -> UnsignedInteger<NUM_LIMBS> {
+        match self {
+            Self::Montgomery(ctx) => ModularRing::one(ctx),
+            Self::Barrett(ctx) => ModularRing::one(ctx),
+        }
+    }
+
+    fn to_repr(&self, a: &UnsignedInteger<NUM_LIMBS>) -> UnsignedInteger<NUM_LIMBS> {
+        match self {
+            Self::Montgomery(ctx) => ctx.to_repr(a),
+            Self::Barrett(ctx) => ctx.to_repr(a),
+        }
+    }
+
+    fn to_standard(&self, a: &UnsignedInteger<NUM_LIMBS>) -> UnsignedInteger<NUM_LIMBS> {
+        match self {
+            Self::Montgomery(ctx) => ctx.to_standard(a),
+            Self::Barrett(ctx) => ctx.to_standard(a),
+        }
+    }
+
+    fn mul(&self, a: &UnsignedInteger<NUM_LIMBS>, b: &UnsignedInteger<NUM_LIMBS>) -> UnsignedInteger<NUM_LIMBS> {
+        match self {
+            Self::Montgomery(ctx) => ModularRing::mul(ctx, a, b),
+            Self::Barrett(ctx) => ModularRing::mul(ctx, a, b),
+        }
+    }
+
+    fn mul_mod(&self, a: &UnsignedInteger<NUM_LIMBS>, b: &UnsignedInteger<NUM_LIMBS>) -> UnsignedInteger<NUM_LIMBS> {
+        match self {
+            Self::Montgomery(ctx) => ModularRing::mul_mod(ctx, a, b),
+            Self::Barrett(ctx) => ModularRing::mul_mod(ctx, a, b),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mul_mod, power_mod};
+
+    type U = UnsignedInteger<2>;
+
+    /// Exercises the trait through a generic caller.
+    fn check_ring<R: ModularRing<2>>(ring: &R) {
+        let modulus = *ring.modulus();
+        let a = U::from_u128(0x0123456789abcdef_fedcba9876543210).div_rem(&modulus).1;
+        let b = U::from_u128(0x243f6a8885a308d3_13198a2e03707344).div_rem(&modulus).1;
+        let exp = U::from_u64(0xb7e151628aed2a6b);
+
+        assert_eq!(ring.to_standard(&ring.to_repr(&a)), a);
+        assert_eq!(ring.to_standard(&ring.one()), U::from_u64(1).div_rem(&modulus).1);
+        assert_eq!(ring.mul_mod(&a, &b), mul_mod(&a, &b, &modulus));
+        assert_eq!(ring.pow_mod(&a, &exp), power_mod(a, exp, &modulus));
+        let (ra, rb) = (ring.to_repr(&a), ring.to_repr(&b));
+        assert_eq!(ring.to_standard(&ring.add(&ra, &rb)), add_mod(&a, &b, &modulus));
+        assert_eq!(ring.to_standard(&ring.sub(&ra, &rb)), sub_mod(&a, &b, &modulus));
+        assert_eq!(ring.to_standard(&ring.square(&ra)), mul_mod(&a, &a, &modulus));
+    }
+
+    #[test]
+    fn reducers_agree_with_reference() {
+        let odd = U::from_u128(0x3fffffffffffffff_ffffffffffffff61);
+        let even = U::from_u128(0x2c5a0f0e1d2b3c4d_5e6f708192a3b4c6);
+        check_ring(&MontgomeryContext::new(&odd).unwrap());
+        check_ring(&BarrettContext::new(&odd).unwrap());
+        check_ring(&BarrettContext::new(&even).unwrap());
+
+        assert!(matches!(Reducer::new(&odd), Some(Reducer::Montgomery(_))));
+        assert!(matches!(Reducer::new(&even), Some(Reducer::Barrett(_))));
+        assert!(Reducer::new(&U::from_u64(0)).is_none());
+        // odd moduli of any width fit Montgomery, even ones need two spare bits
+        assert!(Reducer::new(&U::from_u128(u128::MAX)).is_some());
+        assert!(Reducer::new(&U::from_u128(u128::MAX - 1)).is_none());
+        check_ring(&Reducer::new(&odd).unwrap());
+        check_ring(&Reducer::new(&even).unwrap());
+    }
+}
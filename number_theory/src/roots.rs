@@ -0,0 +1,174 @@
+use lambdaworks_math::unsigned_integer::element::UnsignedInteger;
+
+use crate::PrimeIterator;
+
+/// x^k, or `None` if it overflows.
+fn checked_pow<const NUM_LIMBS: usize>(x: &UnsignedInteger<NUM_LIMBS>, k: u32) -> Option<UnsignedInteger<NUM_LIMBS>> {
+    let zero = UnsignedInteger::<NUM_LIMBS>::from_u64(0);
+    let mut power = UnsignedInteger::<NUM_LIMBS>::from_u64(1);
+    for _ in 0..k {
+        let (hi, lo) = UnsignedInteger::mul(&power, x);
+        if hi != zero {
+            return None;
+        }
+        power = lo;
+    }
+    Some(power)
+}
+
+/// Floor of the square root of `n`, by Newton iteration.
+pub fn isqrt<const NUM_LIMBS: usize>(n: &UnsignedInteger<NUM_LIMBS>) -> UnsignedInteger<NUM_LIMBS> {
+    let zero = UnsignedInteger::<NUM_LIMBS>::from_u64(0);
+    if *n == zero {
+        return zero;
+    }
+    let mut x = UnsignedInteger::<NUM_LIMBS>::from_u64(1) << n.bits_le().div_ceil(2);
+    loop {
+        let (q, _) = n.div_rem(&x);
+        let y = (x + q) >> 1;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+/// Floor of the k-th root of `n`, by Newton iteration. Panics if k = 0.
+pub fn iroot<const NUM_LIMBS: usize>(n: &UnsignedInteger<NUM_LIMBS>, k: u32) -> UnsignedInteger<NUM_LIMBS> {
+    let zero = UnsignedInteger::<NUM_LIMBS>::from_u64(0);
+    let one = UnsignedInteger::<NUM_LIMBS>::from_u64(1);
+    assert!(k > 0, "the zeroth root is undefined");
+    if k == 1 || *n == zero {
+        return *n;
+    }
+    if k == 2 {
+        return isqrt(n);
+    }
+    // 2^k > n, so the root is 1
+    if k as usize >= n.bits_le() {
+        return one;
+    }
+    //
+    // x -> ((k - 1) x + n / x^(k - 1)) / k decreases from any x above the root
+    // until it reaches it. The start 2^ceil(bits / k) is above the root.
+    //
+    let k_minus_one = UnsignedInteger::<NUM_LIMBS>::from_u64(k as u64 - 1);
+    let k_big = UnsignedInteger::<NUM_LIMBS>::from_u64(k as u64);
+    let mut x = one << n.bits_le().div_ceil(k as usize);
+    loop {
+        let q = match checked_pow(&x, k - 1) {
+            Some(power) => n.div_rem(&power).0,
+            None => zero,
+        };
+        let (y, _) = (k_minus_one * x + q).div_rem(&k_big);
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+/// Floor of the cube root of `n`.
+pub fn icbrt<const NUM_LIMBS: usize>(n: &UnsignedInteger<NUM_LIMBS>) -> UnsignedInteger<NUM_LIMBS> {
+    iroot(n, 3)
+}
+
+/// Writes n = base^k with the largest possible k >= 2, so that the base is not
+/// itself a perfect power. Returns `None` if there is no such k or n < 2.
+pub fn is_perfect_power<const NUM_LIMBS: usize>(
+        n: &UnsignedInteger<NUM_LIMBS>,
+    ) -> Option<(UnsignedInteger<NUM_LIMBS>, u32)> {
+    if *n < UnsignedInteger::from_u64(2) {
+        return None;
+    }
+    // Take prime roots while they are exact. A base of at least 2 bounds
+    // the exponent by the bit length.
+    let (mut base, mut k) = (*n, 1);
+    'search: loop {
+        for prime in PrimeIterator::up_to(base.bits_le() as u64 - 1) {
+            let root = iroot(&base, prime as u32);
+            if checked_pow(&root, prime as u32) == Some(base) {
+                base = root;
+                k *= prime as u32;
+                continue 'search;
+            }
+        }
+        break;
+    }
+    (k > 1).then_some((base, k))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type U = UnsignedInteger<2>;
+
+    fn to_u128(a: &U) -> u128 {
+        ((a.limbs[0] as u128) << 64) | a.limbs[1] as u128
+    }
+
+    /// Floor of the k-th root of n by binary search in u128.
+    fn reference_root(n: u128, k: u32) -> u128 {
+        let (mut low, mut high) = (0_u128, if k == 1 { n } else { 1 << (128 / k + 1) });
+        while low < high {
+            let middle = low + (high - low).div_ceil(2);
+            match middle.checked_pow(k) {
+                Some(power) if power <= n => low = middle,
+                _ => high = middle - 1,
+            }
+        }
+        low
+    }
+
+    #[test]
+    fn roots_match_u128_for_small_n() {
+        for n in 0..2000_u128 {
+            for k in 1..=12 {
+                assert_eq!(to_u128(&iroot(&U::from_u128(n), k)), reference_root(n, k));
+            }
+            assert_eq!(to_u128(&isqrt(&U::from_u128(n))), reference_root(n, 2));
+        }
+    }
+
+    #[test]
+    fn roots_match_u128_around_powers() {
+        let mut values = vec![u128::MAX, u128::MAX - 1, 1 << 127];
+        for base in [2_u128, 3, 7, 10, 255, 65535, 1 << 20, 12345678901, u64::MAX as u128] {
+            let mut power = base;
+            while let Some(next) = power.checked_mul(base) {
+                values.extend([next - 1, next, next + 1]);
+                power = next;
+            }
+        }
+        for n in values {
+            for k in (1..=12).chain([31, 32, 33, 63, 64, 65, 127, 128, 129]) {
+                assert_eq!(to_u128(&iroot(&U::from_u128(n), k)), reference_root(n, k), "n = {n}, k = {k}");
+            }
+            assert_eq!(to_u128(&isqrt(&U::from_u128(n))), reference_root(n, 2));
+            assert_eq!(to_u128(&icbrt(&U::from_u128(n))), reference_root(n, 3));
+        }
+    }
+
+    #[test]
+    fn perfect_powers_match_brute_force() {
+        let bound = 1 << 12;
+        let mut largest_exponent = vec![0; bound];
+        for base in 2..64_u64 {
+            let (mut power, mut k) = (base * base, 2);
+            while power < bound as u64 {
+                largest_exponent[power as usize] = largest_exponent[power as usize].max(k);
+                power *= base;
+                k += 1;
+            }
+        }
+        for (n, &k) in largest_exponent.iter().enumerate() {
+            let expected = (k > 0).then(|| (U::from_u128(reference_root(n as u128, k)), k));
+            assert_eq!(is_perfect_power(&U::from_u64(n as u64)), expected);
+        }
+        // 3^80 takes two limbs
+        let n = U::from_u128(3_u128.pow(80));
+        assert_eq!(is_perfect_power(&n), Some((U::from_u64(3), 80)));
+        assert_eq!(is_perfect_power(&(n + U::from_u64(1))), None);
+    }
+}
@@ -0,0 +1,177 @@
+//
+// Segmented sieve of Eratosthenes. Only odd numbers are stored, one segment of
+// SEGMENT_LEN of them at a time, and each segment is sieved by the odd primes up
+// to the square root of the upper limit. Memory stays bounded by the segment and
+// the 6542 primes below 2^16, whatever the range.
+//
+use lambdaworks_math::unsigned_integer::element::UnsignedInteger;
+
+use crate::isqrt;
+
+/// Largest supported upper limit.
+pub const MAX_LIMIT: u64 = 1 << 32;
+
+/// Number of odd integers in a segment.
+const SEGMENT_LEN: usize = 1 << 15;
+
+/// Odd primes up to `bound`, by the plain sieve of Eratosthenes.
+fn odd_primes_up_to(bound: u64) -> Vec<u64> {
+    let mut is_prime = vec![true; bound as usize + 1];
+    let mut primes = vec![];
+    for i in (3..=bound as usize).step_by(2) {
+        if is_prime[i] {
+            primes.push(i as u64);
+            for j in (i * i..=bound as usize).step_by(2 * i) {
+                is_prime[j] = false;
+            }
+        }
+    }
+    primes
+}
+
+/// Lazy iterator over the primes of a range, in increasing order.
+#[derive(Debug, Clone)]
+pub struct PrimeIterator {
+    /// Odd primes up to the square root of `limit`.
+    base_primes: Vec<u64>,
+    limit: u64,
+    yield_two: bool,
+    /// The current segment holds low, low + 2, ..., with `segment[i]` true when low + 2 i is prime.
+    low: u64,
+    segment: Vec<bool>,
+    position: usize,
+    /// First odd integer of the next segment.
+    next_low: u64,
+}
+
+impl PrimeIterator {
+    /// All the primes up to `MAX_LIMIT`.
+    pub fn new() -> Self {
+        Self::between(0, MAX_LIMIT)
+    }
+
+    /// The primes p <= limit.
+    pub fn up_to(limit: u64) -> Self {
+        Self::between(0, limit)
+    }
+
+    /// The primes p with low <= p <= high. Panics if high > `MAX_LIMIT`.
+    pub fn between(low: u64, high: u64) -> Self {
+        assert!(high <= MAX_LIMIT, "primes are only enumerated up to 2^32");
+        let root = isqrt(&UnsignedInteger::<1>::from_u64(high)).limbs[0];
+        let first_odd = low.max(3) | 1;
+        Self {
+            base_primes: odd_primes_up_to(root),
+            limit: high,
+            yield_two: low <= 2 && 2 <= high,
+            low: first_odd,
+            segment: vec![],
+            position: 0,
+            next_low: first_odd,
+        }
+    }
+
+    /// Sieves the next segment, of at most SEGMENT_LEN odd integers.
+    fn sieve_segment(&mut self) {
+        let low = self.next_low;
+        let len = SEGMENT_LEN.min(((self.limit - low) / 2 + 1) as usize);
+        let high = low + 2 * (len as u64 - 1);
+        let mut segment = vec![true; len];
+        for &p in &self.base_primes {
+            if p * p > high {
+                break;
+            }
+            // first odd multiple of p in the segment, from p^2 on
+            let mut multiple = (p * p).max(low.div_ceil(p) * p);
+            if multiple % 2 == 0 {
+                multiple += p;
+            }
+            for j in (((multiple - low) / 2) as usize..len).step_by(p as usize) {
+                segment[j] = false;
+            }
+        }
+        self.low = low;
+        self.segment = segment;
+        self.position = 0;
+        self.next_low = high + 2;
+    }
+}
+
+impl Default for PrimeIterator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Iterator for PrimeIterator {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        if self.yield_two {
+            self.yield_two = false;
+            return Some(2);
+        }
+        loop {
+            while self.position < self.segment.len() {
+                let i = self.position;
+                self.position += 1;
+                if self.segment[i] {
+                    return Some(self.low + 2 * i as u64);
+                }
+            }
+            if self.next_low > self.limit {
+                return None;
+            }
+            self.sieve_segment();
+        }
+    }
+}
+
+/// Number of primes p <= x, for x <= `MAX_LIMIT`.
+pub fn prime_pi(x: u64) -> u64 {
+    PrimeIterator::up_to(x).count() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn naive_primes(bound: u64) -> Vec<u64> {
+        (2..=bound).filter(|&n| (2..n).take_while(|d| d * d <= n).all(|d| n % d != 0)).collect()
+    }
+
+    #[test]
+    fn matches_naive_primes_across_segments() {
+        let expected = naive_primes(200_000);
+        assert_eq!(PrimeIterator::up_to(200_000).collect::<Vec<_>>(), expected);
+        // limits around a segment boundary
+        for limit in [0, 1, 2, 3, 4, 65535, 65537, 65539, 65541] {
+            let expected: Vec<u64> = expected.iter().copied().filter(|&p| p <= limit).collect();
+            assert_eq!(PrimeIterator::up_to(limit).collect::<Vec<_>>(), expected);
+        }
+        let expected: Vec<u64> = expected.iter().copied().filter(|&p| (1000..=1100).contains(&p)).collect();
+        assert_eq!(PrimeIterator::between(1000, 1100).collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn largest_primes_below_limit() {
+        let primes: Vec<u64> = PrimeIterator::between(MAX_LIMIT - 100, MAX_LIMIT).collect();
+        assert_eq!(primes, vec![4294967197, 4294967231, 4294967279, 4294967291]);
+    }
+
+    #[test]
+    fn prime_counting() {
+        assert_eq!(prime_pi(0), 0);
+        assert_eq!(prime_pi(2), 1);
+        assert_eq!(prime_pi(10), 4);
+        assert_eq!(prime_pi(100), 25);
+        assert_eq!(prime_pi(1_000_000), 78498);
+    }
+
+    #[test]
+    #[ignore = "slow without optimizations, run with --release"]
+    fn prime_counting_up_to_limit() {
+        assert_eq!(prime_pi(10_000_000), 664579);
+        assert_eq!(prime_pi(MAX_LIMIT), 203280221);
+    }
+}
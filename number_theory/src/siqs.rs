@@ -0,0 +1,443 @@
+//
+// Self-initializing quadratic sieve. For A = q_1 ... q_s, a product of factor base
+// primes, and B with B^2 = n (mod A), the values (A x + B)^2 - n = A g(x) with
+// g(x) = A x^2 + 2 B x + C are sieved for x in [-M, M) to find those that are
+// smooth over the factor base, up to one large prime. The 2^(s - 1) choices of B
+// for each A are walked in Gray code order, so that the sieve roots are updated
+// with one addition per prime. Once there are more relations y^2 = prod p^e (mod n)
+// than primes, Gaussian elimination over GF(2) gives products with even exponents,
+// that is congruences of squares X^2 = Y^2 (mod n), and gcd(X - Y, n) splits n.
+//
+use std::collections::{HashMap, HashSet};
+
+use lambdaworks_math::unsigned_integer::element::UnsignedInteger;
+use rand::prelude::*;
+
+use crate::{
+    div_u64, gcd, isqrt, rem_u64, sqrt_mod, sub_mod, trailing_zeros, MontgomeryContext, PrimeIterator,
+};
+
+/// Partial relations with a cofactor below this multiple of the largest factor base prime are kept.
+const LARGE_PRIME_MULTIPLIER: u64 = 64;
+
+/// Relations collected beyond the number of columns of the matrix, each one adding a dependency.
+const EXTRA_RELATIONS: usize = 32;
+
+/// Bits of the sieve threshold given up for prime powers and the rounding of the logarithms.
+const THRESHOLD_SLACK: f64 = 4.0;
+
+/// Factor base size and sieve interval of a quadratic sieve run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SiqsParams {
+    /// Number of primes in the factor base, 2 included.
+    pub factor_base_size: usize,
+    /// M, the sieve interval being [-M, M).
+    pub sieve_radius: u64,
+}
+
+impl SiqsParams {
+    /// Parameters suited to an n of the given number of bits.
+    pub fn for_bits(bits: usize) -> Self {
+        let (factor_base_size, sieve_radius) = match bits {
+            0..=80 => (150, 1 << 14),
+            81..=100 => (250, 1 << 14),
+            101..=120 => (400, 1 << 15),
+            121..=140 => (700, 1 << 15),
+            141..=160 => (1_200, 1 << 16),
+            161..=180 => (2_000, 1 << 16),
+            181..=200 => (3_000, 1 << 16),
+            201..=220 => (4_500, 1 << 17),
+            _ => (6_000, 1 << 17),
+        };
+        Self { factor_base_size, sieve_radius }
+    }
+}
+
+/// y^2 = extra^2 * prod p^e (mod n), with the exponents as (column, e) pairs, where
+/// column 0 is the sign and column i + 1 the i-th factor base prime. A column may repeat.
+#[derive(Debug, Clone)]
+struct Relation<const NUM_LIMBS: usize> {
+    y: UnsignedInteger<NUM_LIMBS>,
+    extra: UnsignedInteger<NUM_LIMBS>,
+    exponents: Vec<(usize, u32)>,
+}
+
+/// Base-2 logarithm of a nonzero integer, to double precision.
+fn log2<const NUM_LIMBS: usize>(a: &UnsignedInteger<NUM_LIMBS>) -> f64 {
+    let shift = a.bits_le().saturating_sub(64);
+    ((*a >> shift).limbs[NUM_LIMBS - 1] as f64).log2() + shift as f64
+}
+
+/// base^exp mod modulus, for moduli below 2^64.
+fn pow_u64(base: u64, mut exp: u64, modulus: u64) -> u64 {
+    let (mut base, mut result) = (base as u128 % modulus as u128, 1_u128);
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % modulus as u128;
+        }
+        base = base * base % modulus as u128;
+        exp >>= 1;
+    }
+    result as u64
+}
+
+/// Sum of two integers given as magnitude and sign.
+fn signed_add<const NUM_LIMBS: usize>(
+        (a, a_is_negative): (UnsignedInteger<NUM_LIMBS>, bool),
+        (b, b_is_negative): (UnsignedInteger<NUM_LIMBS>, bool),
+    ) -> (UnsignedInteger<NUM_LIMBS>, bool) {
+    if a_is_negative == b_is_negative {
+        (a + b, a_is_negative)
+    } else if a >= b {
+        (a - b, a_is_negative)
+    } else {
+        (b - a, b_is_negative)
+    }
+}
+
+/// Sets of relations, given by the columns where they have an odd exponent, whose
+/// exponent vectors add up to zero over GF(2). Each row is reduced by the pivots
+/// found so far; a row that vanishes gives the relations it was built from.
+fn dependencies(rows: &[Vec<usize>], num_columns: usize) -> Vec<Vec<usize>> {
+    // each row holds its columns followed by the identity, which tracks its combination
+    let words = (num_columns + rows.len()).div_ceil(64);
+    let mut matrix: Vec<Vec<u64>> = rows
+        .iter()
+        .enumerate()
+        .map(|(i, columns)| {
+            let mut row = vec![0_u64; words];
+            for &column in columns {
+                row[column / 64] ^= 1 << (column % 64);
+            }
+            row[(num_columns + i) / 64] |= 1 << ((num_columns + i) % 64);
+            row
+        })
+        .collect();
+
+    let bit = |row: &[u64], column: usize| (row[column / 64] >> (column % 64)) & 1 == 1;
+    let mut is_pivot = vec![false; rows.len()];
+    for column in 0..num_columns {
+        let Some(pivot) = (0..rows.len()).find(|&r| !is_pivot[r] && bit(&matrix[r], column)) else {
+            continue;
+        };
+        is_pivot[pivot] = true;
+        let pivot_row = matrix[pivot].clone();
+        for (r, row) in matrix.iter_mut().enumerate() {
+            if !is_pivot[r] && bit(row, column) {
+                for (word, pivot_word) in row.iter_mut().zip(&pivot_row) {
+                    *word ^= pivot_word;
+                }
+            }
+        }
+    }
+
+    (0..rows.len())
+        .filter(|&r| !is_pivot[r])
+        .map(|r| (0..rows.len()).filter(|&i| bit(&matrix[r], num_columns + i)).collect())
+        .collect()
+}
+
+/// Self-initializing quadratic sieve for odd n > 1 that is not a prime power and
+/// leaves room for two more bits in `NUM_LIMBS` limbs. Returns a nontrivial factor of n.
+pub fn siqs<const NUM_LIMBS: usize>(
+        n: &UnsignedInteger<NUM_LIMBS>,
+        params: &SiqsParams,
+    ) -> Option<UnsignedInteger<NUM_LIMBS>> {
+    let zero = UnsignedInteger::<NUM_LIMBS>::from_u64(0);
+    let one = UnsignedInteger::<NUM_LIMBS>::from_u64(1);
+    // |A x + B| stays a little above sqrt(2 n), so its square needs n < 2^(64 NUM_LIMBS - 2)
+    if n.bits_le() + 2 > 64 * NUM_LIMBS {
+        return None;
+    }
+    let ctx = MontgomeryContext::new(n)?;
+    let root = isqrt(n);
+    if root * root == *n {
+        return Some(root);
+    }
+
+    // Factor base: 2 and the odd primes p with (n / p) = 1, with the roots t of t^2 = n (mod p).
+    let mut primes = vec![2_u64];
+    let mut roots = vec![1_u64];
+    for p in PrimeIterator::new().skip(1) {
+        if primes.len() == params.factor_base_size {
+            break;
+        }
+        let n_mod_p = UnsignedInteger::<1>::from_u64(rem_u64(n, p));
+        let Some((t, _)) = sqrt_mod(&n_mod_p, &UnsignedInteger::from_u64(p)) else {
+            continue;
+        };
+        if t.limbs[0] == 0 {
+            return (*n != UnsignedInteger::from_u64(p)).then_some(UnsignedInteger::from_u64(p));
+        }
+        primes.push(p);
+        roots.push(t.limbs[0]);
+    }
+    let logs: Vec<u8> = primes.iter().map(|&p| (p as f64).log2().round() as u8).collect();
+    let large_prime_bound = primes[primes.len() - 1] * LARGE_PRIME_MULTIPLIER;
+
+    // A is about sqrt(2 n) / M, so that |g(x)| is at most about M sqrt(n / 2) on the interval.
+    let m = params.sieve_radius;
+    let log_target = (log2(n) + 1.0) / 2.0 - (m as f64).log2();
+    let log_largest = (primes[primes.len() - 1] as f64).log2();
+    let mut s = ((log_target / 11.0).round() as usize).max(1);
+    while log_target / (s as f64) > log_largest - 1.0 {
+        s += 1;
+    }
+    let log_ideal = log_target / s as f64;
+    let mut pool = vec![];
+    let mut width = 1.0;
+    while pool.len() < s + 4 && width < 64.0 {
+        pool = (1..primes.len()).filter(|&i| ((primes[i] as f64).log2() - log_ideal).abs() <= width).collect();
+        width += 1.0;
+    }
+    if pool.len() < s {
+        return None;
+    }
+    let threshold = (log2(n) / 2.0 + (m as f64).log2() - 0.5 - (large_prime_bound as f64).log2() - THRESHOLD_SLACK)
+        .max(0.0) as u8;
+
+    let num_columns = primes.len() + 1;
+    let mut relations: Vec<Relation<NUM_LIMBS>> = vec![];
+    let mut partials: HashMap<u64, Relation<NUM_LIMBS>> = HashMap::new();
+    let mut used_a = HashSet::new();
+    let mut sieve = vec![0_u8; 2 * m as usize];
+    let mut rng = rand::thread_rng();
+
+    while relations.len() < num_columns + EXTRA_RELATIONS {
+        // s - 1 random primes of the pool, completed by the one bringing A closest to the target.
+        let mut a_indices: Vec<usize> = pool.choose_multiple(&mut rng, s - 1).copied().collect();
+        let log_a: f64 = a_indices.iter().map(|&i| (primes[i] as f64).log2()).sum();
+        let last = (1..primes.len())
+            .filter(|i| !a_indices.contains(i))
+            .min_by(|&i, &j| {
+                let distance = |k: usize| (log_a + (primes[k] as f64).log2() - log_target).abs();
+                distance(i).total_cmp(&distance(j))
+            })?;
+        a_indices.push(last);
+        a_indices.sort();
+        if !used_a.insert(a_indices.clone()) {
+            continue;
+        }
+        let mut in_a = vec![false; primes.len()];
+        let mut a = one;
+        for &i in &a_indices {
+            in_a[i] = true;
+            a = a * UnsignedInteger::from_u64(primes[i]);
+        }
+
+        // B = sum of B_l, with B_l = (A / q_l) gamma_l, B_l^2 = n (mod q_l) and B_l = 0 (mod q_j), j != l.
+        let b_terms: Vec<UnsignedInteger<NUM_LIMBS>> = a_indices
+            .iter()
+            .map(|&i| {
+                let q = primes[i];
+                let (a_over_q, _) = div_u64(&a, q);
+                let mut gamma = roots[i] * pow_u64(rem_u64(&a_over_q, q), q - 2, q) % q;
+                if gamma > q / 2 {
+                    gamma = q - gamma;
+                }
+                a_over_q * UnsignedInteger::from_u64(gamma)
+            })
+            .collect();
+        let mut b = (b_terms.iter().fold(zero, |sum, term| sum + *term), false);
+
+        // Sieve roots x = A^(-1) (±t - B) (mod p) and their steps 2 B_l A^(-1) (mod p).
+        let mut a_inverses = vec![0; primes.len()];
+        let mut deltas = vec![vec![0; primes.len()]; s - 1];
+        let mut solutions = vec![(0, 0); primes.len()];
+        for i in 1..primes.len() {
+            if in_a[i] {
+                continue;
+            }
+            let p = primes[i];
+            a_inverses[i] = pow_u64(rem_u64(&a, p), p - 2, p);
+            for (l, delta) in deltas.iter_mut().enumerate() {
+                delta[i] = 2 * (rem_u64(&b_terms[l], p) as u128 * a_inverses[i] as u128 % p as u128) as u64 % p;
+            }
+            let b_mod_p = rem_u64(&b.0, p);
+            let root = |t: u64| ((t + p - b_mod_p) as u128 * a_inverses[i] as u128 % p as u128) as u64;
+            solutions[i] = (root(roots[i]), root(p - roots[i]));
+        }
+
+        let mut b_is_negative = vec![false; s - 1];
+        for polynomial in 0..1_usize << (s - 1) {
+            if polynomial > 0 {
+                // Gray code: flip the sign of B_l, so B moves by -2 B_l or 2 B_l.
+                let l = polynomial.trailing_zeros() as usize;
+                b_is_negative[l] = !b_is_negative[l];
+                b = signed_add(b, (b_terms[l] << 1, b_is_negative[l]));
+                for i in 1..primes.len() {
+                    if in_a[i] {
+                        continue;
+                    }
+                    let (p, delta) = (primes[i], deltas[l][i]);
+                    let step = |x: u64| if b_is_negative[l] { (x + delta) % p } else { (x + p - delta) % p };
+                    solutions[i] = (step(solutions[i].0), step(solutions[i].1));
+                }
+            }
+
+            sieve.fill(0);
+            for i in 1..primes.len() {
+                if in_a[i] {
+                    continue;
+                }
+                let p = primes[i];
+                for solution in [solutions[i].0, solutions[i].1] {
+                    let mut j = ((solution + m % p) % p) as usize;
+                    while j < sieve.len() {
+                        sieve[j] = sieve[j].saturating_add(logs[i]);
+                        j += p as usize;
+                    }
+                }
+            }
+
+            for (j, _) in sieve.iter().enumerate().filter(|(_, &log)| log >= threshold) {
+                let x = j as i64 - m as i64;
+                // y = A x + B and A g(x) = y^2 - n
+                let (y, _) = signed_add((a * UnsignedInteger::from_u64(x.unsigned_abs()), x < 0), b);
+                let (_, y_squared) = UnsignedInteger::mul(&y, &y);
+                let (mut g, g_is_negative) = if y_squared >= *n {
+                    ((y_squared - n).div_rem(&a).0, false)
+                } else {
+                    ((n - y_squared).div_rem(&a).0, true)
+                };
+
+                let mut exponents: Vec<(usize, u32)> = a_indices.iter().map(|&i| (i + 1, 1)).collect();
+                if g_is_negative {
+                    exponents.push((0, 1));
+                }
+                let twos = trailing_zeros(&g);
+                if twos > 0 {
+                    g >>= twos;
+                    exponents.push((1, twos as u32));
+                }
+                for i in 1..primes.len() {
+                    let p = primes[i];
+                    let x_mod_p = x.rem_euclid(p as i64) as u64;
+                    if !in_a[i] && x_mod_p != solutions[i].0 && x_mod_p != solutions[i].1 {
+                        continue;
+                    }
+                    let mut e = 0;
+                    while rem_u64(&g, p) == 0 {
+                        (g, _) = div_u64(&g, p);
+                        e += 1;
+                    }
+                    if e > 0 {
+                        exponents.push((i + 1, e));
+                    }
+                }
+
+                let (_, y) = y.div_rem(n);
+                let relation = Relation { y, extra: one, exponents };
+                if g == one {
+                    relations.push(relation);
+                } else if g.bits_le() <= 64 && g.limbs[NUM_LIMBS - 1] < large_prime_bound {
+                    // Two partial relations with the same large prime L make a full one, with L^2 as extra.
+                    let large_prime = g.limbs[NUM_LIMBS - 1];
+                    match partials.get(&large_prime) {
+                        Some(other) if other.y != relation.y => relations.push(Relation {
+                            y: ctx.mul_mod(&other.y, &relation.y),
+                            extra: g,
+                            exponents: [other.exponents.as_slice(), relation.exponents.as_slice()].concat(),
+                        }),
+                        Some(_) => {}
+                        None => {
+                            partials.insert(large_prime, relation);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // X = prod y and Y = prod extra * prod p^(e / 2), with X^2 = Y^2 (mod n).
+    let rows: Vec<Vec<usize>> = relations
+        .iter()
+        .map(|relation| {
+            let mut columns: Vec<usize> = vec![];
+            for &(column, e) in &relation.exponents {
+                if e % 2 == 1 {
+                    match columns.iter().position(|&c| c == column) {
+                        Some(k) => {
+                            columns.swap_remove(k);
+                        }
+                        None => columns.push(column),
+                    }
+                }
+            }
+            columns
+        })
+        .collect();
+    for dependency in dependencies(&rows, num_columns) {
+        let mut x = one;
+        let mut y = one;
+        let mut exponents = vec![0_u64; num_columns];
+        for &r in &dependency {
+            x = ctx.mul_mod(&x, &relations[r].y);
+            y = ctx.mul_mod(&y, &relations[r].extra);
+            for &(column, e) in &relations[r].exponents {
+                exponents[column] += e as u64;
+            }
+        }
+        for (i, &p) in primes.iter().enumerate() {
+            let half = exponents[i + 1] / 2;
+            if half > 0 {
+                let p_mont = ctx.to_mont(&UnsignedInteger::from_u64(p));
+                let power = ctx.from_mont(&ctx.pow(&p_mont, &UnsignedInteger::from_u64(half)));
+                y = ctx.mul_mod(&y, &power);
+            }
+        }
+        let g = gcd(&sub_mod(&x, &y, n), n);
+        if g != one && g != *n {
+            return Some(g);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dependencies_sum_to_zero() {
+        let rows = vec![vec![0, 1], vec![1, 2], vec![0, 2], vec![3], vec![0, 3], vec![1]];
+        let found = dependencies(&rows, 4);
+        // 6 rows of rank 4
+        assert_eq!(found.len(), 2);
+        for dependency in found {
+            let mut sum = [0; 4];
+            for r in dependency {
+                for &column in &rows[r] {
+                    sum[column] ^= 1;
+                }
+            }
+            assert_eq!(sum, [0; 4]);
+        }
+    }
+
+    #[test]
+    fn siqs_splits_semiprimes() {
+        // 824377536319 * 868681941263
+        let n = UnsignedInteger::<2>::from_hex_unchecked("97a50b79bb185088c3b1");
+        let g = siqs(&n, &SiqsParams::for_bits(n.bits_le())).unwrap();
+        assert!(g == UnsignedInteger::from_u64(824377536319) || g == UnsignedInteger::from_u64(868681941263));
+
+        // 644720693563537 * 1069316540739163
+        let n = UnsignedInteger::<2>::from_hex_unchecked("8b39b004c719e40468eafb18b");
+        let g = siqs(&n, &SiqsParams::for_bits(n.bits_le())).unwrap();
+        assert!(g == UnsignedInteger::from_u64(644720693563537) || g == UnsignedInteger::from_u64(1069316540739163));
+    }
+
+    #[test]
+    #[ignore = "slow without optimizations, run with --release"]
+    fn siqs_splits_200_bit_semiprime() {
+        // 804142363293393855323353430543 * 1003345375318229866682188571507
+        let n = UnsignedInteger::<4>::from_hex_unchecked(
+            "80892c91e80dd2382bd38a44da0afe8dcee04dd7f6c6bba1bd"
+        );
+        let g = siqs(&n, &SiqsParams::for_bits(n.bits_le())).unwrap();
+        let p = UnsignedInteger::<4>::from_hex_unchecked("a2652f8ff842a2f9da1b4ba0f");
+        let q = UnsignedInteger::<4>::from_hex_unchecked("ca9fbd797aa59c1698d242373");
+        assert!(g == p || g == q);
+    }
+}
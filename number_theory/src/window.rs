@@ -0,0 +1,374 @@
+use lambdaworks_math::cyclic_group::IsGroup;
+use lambdaworks_math::unsigned_integer::element::UnsignedInteger;
+
+use crate::{bit_at, mul_mod, ModularRing, Reducer, LIMB_SIZE_BIT};
+
+/// Largest supported window size.
+pub const MAX_WINDOW_SIZE: usize = 8;
+
+/// Returns the bits `low..=high` of `a` as an integer.
+fn bits_between<const NUM_LIMBS: usize>(a: &UnsignedInteger<NUM_LIMBS>, high: usize, low: usize) -> usize {
+    (low..=high).rev().fold(0, |acc, i| (acc << 1) | bit_at(a, i) as usize)
+}
+
+/// Left-to-right sliding window exponentiation over any representation `T`
+/// with multiplication `mul` and identity `one`.
+/// Only the odd powers base, base^3, ..., base^(2^W - 1) are precomputed.
+/// With W = 1 this is plain square-and-multiply.
+pub fn sliding_window_pow<T: Clone, const W: usize, const NUM_LIMBS: usize>(
+        base: &T,
+        exp: &UnsignedInteger<NUM_LIMBS>,
+        one: &T,
+        mut mul: impl FnMut(&T, &T) -> T,
+    ) -> T {
+    assert!((1..=MAX_WINDOW_SIZE).contains(&W), "window size out of range");
+
+    let mut table = vec![base.clone()];
+    if W > 1 {
+        let base_squared = mul(base, base);
+        for i in 1..1 << (W - 1) {
+            let next = mul(&table[i - 1], &base_squared);
+            table.push(next);
+        }
+    }
+
+    // `None` stands for the identity, so no squarings are spent on it.
+    let mut result: Option<T> = None;
+    let mut i = exp.bits_le();
+    while i > 0 {
+        let high = i - 1;
+        if !bit_at(exp, high) {
+            result = result.map(|r| mul(&r, &r));
+            i -= 1;
+            continue;
+        }
+        // Longest window of at most W bits ending in a set bit.
+        let mut low = high.saturating_sub(W - 1);
+        while !bit_at(exp, low) {
+            low += 1;
+        }
+        let power = &table[bits_between(exp, high, low) >> 1];
+        result = Some(match result {
+            None => power.clone(),
+            Some(mut r) => {
+                for _ in low..=high {
+                    r = mul(&r, &r);
+                }
+                mul(&r, power)
+            }
+        });
+        i = low;
+    }
+    result.unwrap_or_else(|| one.clone())
+}
+
+/// Left-to-right fixed window (2^W-ary) exponentiation over any representation `T`
+/// with multiplication `mul` and identity `one`.
+/// All the powers base^0, ..., base^(2^W - 1) are precomputed.
+pub fn fixed_window_pow<T: Clone, const W: usize, const NUM_LIMBS: usize>(
+        base: &T,
+        exp: &UnsignedInteger<NUM_LIMBS>,
+        one: &T,
+        mut mul: impl FnMut(&T, &T) -> T,
+    ) -> T {
+    assert!((1..=MAX_WINDOW_SIZE).contains(&W), "window size out of range");
+
+    let mut table = vec![one.clone(), base.clone()];
+    for i in 2..1 << W {
+        let next = mul(&table[i - 1], base);
+        table.push(next);
+    }
+
+    let num_digits = exp.bits_le().div_ceil(W);
+    let mut result: Option<T> = None;
+    for d in (0..num_digits).rev() {
+        let low = d * W;
+        let high = (low + W - 1).min(exp.bits_le() - 1);
+        let digit = bits_between(exp, high, low);
+        result = match result {
+            None => Some(table[digit].clone()),
+            Some(mut r) => {
+                for _ in 0..W {
+                    r = mul(&r, &r);
+                }
+                if digit != 0 {
+                    r = mul(&r, &table[digit]);
+                }
+                Some(r)
+            }
+        };
+    }
+    result.unwrap_or_else(|| one.clone())
+}
+
+/// Joint table of every product of base_i^d_i with digits d_i < 2^W,
+/// at index sum d_i 2^(W i).
+fn joint_table<T: Clone, const W: usize, const NUM_LIMBS: usize>(
+        pairs: &[(T, UnsignedInteger<NUM_LIMBS>)],
+        one: &T,
+        mul: &mut impl FnMut(&T, &T) -> T,
+    ) -> Vec<T> {
+    let mut table = vec![one.clone()];
+    for (base, _) in pairs {
+        let block = table.len();
+        for d in 1..1 << W {
+            for j in 0..block {
+                let next = if d == 1 && j == 0 { base.clone() } else { mul(&table[(d - 1) * block + j], base) };
+                table.push(next);
+            }
+        }
+    }
+    table
+}
+
+/// Straus's method with the bases split into groups of `group_size`, each with its
+/// own joint table. All groups share one chain of squarings, and each W-bit digit
+/// position costs one multiplication per group.
+fn grouped_multi_pow<T: Clone, const W: usize, const NUM_LIMBS: usize>(
+        pairs: &[(T, UnsignedInteger<NUM_LIMBS>)],
+        group_size: usize,
+        one: &T,
+        mut mul: impl FnMut(&T, &T) -> T,
+    ) -> T {
+    let groups: Vec<_> = pairs.chunks(group_size.max(1)).collect();
+    let tables: Vec<Vec<T>> = groups.iter().map(|group| joint_table::<_, W, NUM_LIMBS>(group, one, &mut mul)).collect();
+
+    let width = NUM_LIMBS * LIMB_SIZE_BIT;
+    let num_digits = pairs.iter().map(|(_, exp)| exp.bits_le()).max().unwrap_or(0).div_ceil(W);
+    let mut result: Option<T> = None;
+    for d in (0..num_digits).rev() {
+        let low = d * W;
+        let high = (low + W - 1).min(width - 1);
+        if let Some(r) = result.as_mut() {
+            for _ in 0..W {
+                *r = mul(r, r);
+            }
+        }
+        for (group, table) in groups.iter().zip(&tables) {
+            let index = group.iter().rev().fold(0, |index, (_, exp)| (index << W) | bits_between(exp, high, low));
+            if index != 0 {
+                result = Some(match result {
+                    None => table[index].clone(),
+                    Some(r) => mul(&r, &table[index]),
+                });
+            }
+        }
+    }
+    result.unwrap_or_else(|| one.clone())
+}
+
+/// Straus's method for the product of base_i^exp_i over any representation `T`
+/// with multiplication `mul` and identity `one`. A joint table holds every product
+/// of base_i^d_i with digits d_i < 2^W, so all the bases share one chain of
+/// squarings and each W-bit digit position costs a single multiplication.
+/// Panics if the table would exceed 2^MAX_WINDOW_SIZE entries.
+pub fn multi_pow<T: Clone, const W: usize, const NUM_LIMBS: usize>(
+        pairs: &[(T, UnsignedInteger<NUM_LIMBS>)],
+        one: &T,
+        mul: impl FnMut(&T, &T) -> T,
+    ) -> T {
+    assert!((1..=MAX_WINDOW_SIZE).contains(&W), "window size out of range");
+    assert!(W * pairs.len() <= MAX_WINDOW_SIZE, "too many bases for the window size");
+    grouped_multi_pow::<_, W, NUM_LIMBS>(pairs, pairs.len(), one, mul)
+}
+
+/// `multi_pow` with a window suited to the number of bases: 2-bit digits for one
+/// or two bases, and single bits beyond that, with a 16-entry table for each group
+/// of four bases on a shared chain of squarings.
+fn straus<T: Clone, const NUM_LIMBS: usize>(
+        pairs: &[(T, UnsignedInteger<NUM_LIMBS>)],
+        one: &T,
+        mul: impl FnMut(&T, &T) -> T,
+    ) -> T {
+    if pairs.len() <= 2 {
+        return multi_pow::<_, 2, NUM_LIMBS>(pairs, one, mul);
+    }
+    grouped_multi_pow::<_, 1, NUM_LIMBS>(pairs, 4, one, mul)
+}
+
+/// Product of base_i^exp_i modulo m, such as g^a h^b in a signature check,
+/// for about the cost of a single exponentiation when there are two bases.
+/// Odd moduli and most even ones go through a `Reducer`, others through `mul_mod`.
+pub fn multi_power_mod<const NUM_LIMBS: usize>(
+        pairs: &[(UnsignedInteger<NUM_LIMBS>, UnsignedInteger<NUM_LIMBS>)],
+        modulus: &UnsignedInteger<NUM_LIMBS>,
+    ) -> UnsignedInteger<NUM_LIMBS> {
+    match Reducer::new(modulus) {
+        Some(ring) => {
+            let pairs: Vec<_> = pairs.iter().map(|(base, exp)| (ring.to_repr(base), *exp)).collect();
+            ring.to_standard(&straus(&pairs, &ring.one(), |a, b| ring.mul(a, b)))
+        }
+        None => {
+            let pairs: Vec<_> = pairs.iter().map(|(base, exp)| (base.div_rem(modulus).1, *exp)).collect();
+            let (_, one) = UnsignedInteger::from_u64(1).div_rem(modulus);
+            straus(&pairs, &one, |a, b| mul_mod(a, b, modulus))
+        }
+    }
+}
+
+/// Combination of exp_i applications of the group operation to element_i, such as
+/// a G + b H for elliptic curve points, by Straus's method.
+pub fn multi_operate_with_self<G: IsGroup, const NUM_LIMBS: usize>(
+        pairs: &[(G, UnsignedInteger<NUM_LIMBS>)],
+    ) -> G {
+    straus(pairs, &G::neutral_element(), |a, b| a.operate_with(b))
+}
+
+/// Modular exponentiation with a sliding window of W bits.
+pub fn power_mod_window<const W: usize, const NUM_LIMBS: usize>(
+        base: UnsignedInteger<NUM_LIMBS>,
+        exp: UnsignedInteger<NUM_LIMBS>,
+        modulus: &UnsignedInteger<NUM_LIMBS>,
+    ) -> UnsignedInteger<NUM_LIMBS> {
+    let (_, base) = base.div_rem(modulus);
+    let (_, one) = UnsignedInteger::from_u64(1).div_rem(modulus);
+    sliding_window_pow::<_, W, NUM_LIMBS>(&base, &exp, &one, |a, b| mul_mod(a, b, modulus))
+}
+
+/// Modular exponentiation with a fixed window of W bits.
+pub fn power_mod_fixed_window<const W: usize, const NUM_LIMBS: usize>(
+        base: UnsignedInteger<NUM_LIMBS>,
+        exp: UnsignedInteger<NUM_LIMBS>,
+        modulus: &UnsignedInteger<NUM_LIMBS>,
+    ) -> UnsignedInteger<NUM_LIMBS> {
+    let (_, base) = base.div_rem(modulus);
+    let (_, one) = UnsignedInteger::from_u64(1).div_rem(modulus);
+    fixed_window_pow::<_, W, NUM_LIMBS>(&base, &exp, &one, |a, b| mul_mod(a, b, modulus))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::power_mod;
+
+    fn check_against_reference<const NUM_LIMBS: usize>(
+            base: UnsignedInteger<NUM_LIMBS>,
+            exp: UnsignedInteger<NUM_LIMBS>,
+            modulus: UnsignedInteger<NUM_LIMBS>,
+        ) {
+        let expected = power_mod(base, exp, &modulus);
+        assert_eq!(power_mod_window::<1, NUM_LIMBS>(base, exp, &modulus), expected);
+        assert_eq!(power_mod_window::<3, NUM_LIMBS>(base, exp, &modulus), expected);
+        assert_eq!(power_mod_window::<5, NUM_LIMBS>(base, exp, &modulus), expected);
+        assert_eq!(power_mod_fixed_window::<1, NUM_LIMBS>(base, exp, &modulus), expected);
+        assert_eq!(power_mod_fixed_window::<4, NUM_LIMBS>(base, exp, &modulus), expected);
+        assert_eq!(power_mod_fixed_window::<7, NUM_LIMBS>(base, exp, &modulus), expected);
+    }
+
+    #[test]
+    fn window_variants_match_power_mod_small_exponents() {
+        let modulus = UnsignedInteger::<1>::from_u64(1_000_000_007);
+        let base = UnsignedInteger::<1>::from_u64(123_456_789);
+        for e in 0..300 {
+            check_against_reference(base, UnsignedInteger::from_u64(e), modulus);
+        }
+    }
+
+    #[test]
+    fn window_variants_match_power_mod_full_width() {
+        let modulus = UnsignedInteger::<4>::from_hex_unchecked(
+            "73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001"
+        );
+        let base = UnsignedInteger::<4>::from_hex_unchecked(
+            "e4840ac57f86f5e293b1d67bc8de5d9a12a70a615d0b8e4d2fc5e69ac5db47f"
+        );
+        let exp = UnsignedInteger::<4>::from_hex_unchecked(
+            "f1e2d3c4b5a6978801234567890abcdef0fedcba9876543210aabbccddeeff00"
+        );
+        check_against_reference(base, exp, modulus);
+    }
+
+    #[test]
+    fn sliding_window_uses_fewer_multiplications() {
+        let exp = UnsignedInteger::<4>::from_hex_unchecked(
+            "f1e2d3c4b5a6978801234567890abcdef0fedcba9876543210aabbccddeeff00"
+        );
+        let count = |w: usize| {
+            let mut count = 0;
+            let mul = |a: &u64, b: &u64| { count += 1; a.wrapping_add(*b) };
+            match w {
+                1 => sliding_window_pow::<_, 1, 4>(&1, &exp, &0, mul),
+                _ => sliding_window_pow::<_, 4, 4>(&1, &exp, &0, mul),
+            };
+            count
+        };
+        assert!(count(4) < count(1));
+    }
+
+    #[test]
+    fn multi_power_mod_matches_product_of_powers() {
+        let exps = [
+            "f1e2d3c4b5a6978801234567890abcdef0fedcba9876543210aabbccddeeff00",
+            "1",
+            "0",
+            "8000000000000000000000000000000000000000000000000000000000000000",
+            "3a0c92075c0dbf3b8acbc5f96ce3",
+            "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+        ].map(UnsignedInteger::<4>::from_hex_unchecked);
+        let moduli = [
+            "73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001",
+            "3a0c92075c0dbf3b8acbc5f96ce3f0ad2f3e8f3a2c2b6c4e1ee4f0b6a1d2c3e4",
+            // even and too wide for Barrett reduction
+            "c0000000000000000000000000000000000000000000000000000000000000a2",
+        ].map(UnsignedInteger::<4>::from_hex_unchecked);
+        for modulus in moduli {
+            let pairs: Vec<_> = exps.iter().enumerate()
+                .map(|(i, exp)| ((modulus >> (i + 1)) + UnsignedInteger::from_u64(0x9e3779b97f4a7c15), *exp))
+                .collect();
+            for k in 0..=pairs.len() {
+                let expected = pairs[..k].iter().fold(UnsignedInteger::from_u64(1), |product, (base, exp)| {
+                    mul_mod(&product, &power_mod(*base, *exp, &modulus), &modulus)
+                });
+                assert_eq!(multi_power_mod(&pairs[..k], &modulus), expected.div_rem(&modulus).1);
+            }
+        }
+    }
+
+    #[test]
+    fn straus_shares_squarings() {
+        let exps = [
+            "f1e2d3c4b5a6978801234567890abcdef0fedcba9876543210aabbccddeeff00",
+            "73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001",
+        ].map(UnsignedInteger::<4>::from_hex_unchecked);
+        let mut count = 0;
+        let pairs = [(1_u64, exps[0]), (2, exps[1])];
+        let result = multi_pow::<_, 2, 4>(&pairs, &0, |a, b| { count += 1; a.wrapping_add(*b) });
+        // additive notation: the result is 1 * exps[0] + 2 * exps[1] mod 2^64
+        assert_eq!(result, exps[0].limbs[3].wrapping_add(exps[1].limbs[3].wrapping_mul(2)));
+        // one chain of 256 squarings, at most 128 digit multiplications and a 16-entry table
+        assert!(count <= 256 + 128 + 16);
+    }
+
+    #[test]
+    fn straus_shares_squarings_across_groups() {
+        let exps: Vec<UnsignedInteger<4>> = (1..=8_u64)
+            .map(|i| UnsignedInteger::from_limbs([i.wrapping_mul(0x9e3779b97f4a7c15) | 1 << 63, 0, i, !i]))
+            .collect();
+        let pairs: Vec<(u64, UnsignedInteger<4>)> = exps.iter().enumerate().map(|(i, exp)| (i as u64 + 1, *exp)).collect();
+        let mut count = 0;
+        let result = straus(&pairs, &0, |a: &u64, b: &u64| { count += 1; a.wrapping_add(*b) });
+        let expected = pairs.iter().fold(0_u64, |acc, (base, exp)| acc.wrapping_add(base.wrapping_mul(exp.limbs[3])));
+        assert_eq!(result, expected);
+        // two groups of four bases: one chain of 256 squarings, at most 2 * 256 digit
+        // multiplications and two 16-entry tables
+        assert!(count <= 256 + 2 * 256 + 2 * 16);
+    }
+
+    #[test]
+    fn multi_operate_with_self_on_curve_points() {
+        use lambdaworks_math::elliptic_curve::{
+            short_weierstrass::curves::bls12_381::curve::BLS12381Curve,
+            traits::IsEllipticCurve,
+        };
+        let g = BLS12381Curve::generator();
+        let h = g.operate_with_self(0x243f6a8885a308d3_u64);
+        let (a, b) = (
+            UnsignedInteger::<4>::from_hex_unchecked("1b2a3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f7081"),
+            UnsignedInteger::<4>::from_hex_unchecked("73eda753299d7d483339d80809a1d80553bda402fffe5bfe"),
+        );
+        let expected = g.operate_with_self(a).operate_with(&h.operate_with_self(b));
+        assert_eq!(multi_operate_with_self(&[(g.clone(), a), (h.clone(), b)]), expected);
+        assert_eq!(multi_operate_with_self(&[(g.clone(), a), (h, b), (g.clone(), b)]), expected.operate_with(&g.operate_with_self(b)));
+        assert!(multi_operate_with_self::<_, 4>(&[(g, UnsignedInteger::from_u64(0))]).is_neutral_element());
+    }
+}
@@ -0,0 +1,87 @@
+//
+// dudect-style timing leakage tests (https://eprint.iacr.org/2016/1123).
+// Executions are split into two classes of inputs, chosen at random and
+// interleaved, and Welch's t-test decides whether the two timing
+// distributions differ. They depend on the machine load, so they are
+// ignored by default. Run them locally with
+//
+//     cargo test --release --test timing -- --ignored
+//
+use std::time::Instant;
+
+use lambdaworks_math::unsigned_integer::element::UnsignedInteger;
+use number_theory::{power_mod, power_mod_ct};
+use rand::prelude::*;
+
+const NUM_LIMBS: usize = 4;
+const SAMPLES: usize = 20_000;
+// dudect's threshold: above it, timing is considered data dependent.
+const T_THRESHOLD: f64 = 10.0;
+// Measurements above this percentile are discarded as noise.
+const CROP_PERCENTILE: f64 = 0.9;
+
+fn welch_t(class0: &[f64], class1: &[f64]) -> f64 {
+    let mean_var = |xs: &[f64]| {
+        let n = xs.len() as f64;
+        let mean = xs.iter().sum::<f64>() / n;
+        let var = xs.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / (n - 1.0);
+        (mean, var, n)
+    };
+    let (m0, v0, n0) = mean_var(class0);
+    let (m1, v1, n1) = mean_var(class1);
+    (m0 - m1) / (v0 / n0 + v1 / n1).sqrt()
+}
+
+// Times `f` on exponents of class 0 (always `fixed`) and class 1 (random)
+// and returns Welch's t statistic of the cropped measurements.
+fn leakage_t_statistic(
+        fixed: UnsignedInteger<NUM_LIMBS>,
+        f: impl Fn(UnsignedInteger<NUM_LIMBS>) -> UnsignedInteger<NUM_LIMBS>,
+    ) -> f64 {
+    let mut rng = rand::thread_rng();
+    let mut measurements: Vec<(bool, f64)> = Vec::with_capacity(SAMPLES);
+    for _ in 0..SAMPLES {
+        let class = rng.gen::<bool>();
+        let exp = if class {
+            UnsignedInteger::from_limbs(rng.gen())
+        } else {
+            fixed
+        };
+        let start = Instant::now();
+        std::hint::black_box(f(std::hint::black_box(exp)));
+        measurements.push((class, start.elapsed().as_nanos() as f64));
+    }
+
+    let mut sorted: Vec<f64> = measurements.iter().map(|(_, t)| *t).collect();
+    sorted.sort_by(f64::total_cmp);
+    let crop = sorted[(CROP_PERCENTILE * SAMPLES as f64) as usize];
+
+    let class_times = |class: bool| -> Vec<f64> {
+        measurements.iter().filter(|(c, t)| *c == class && *t <= crop).map(|(_, t)| *t).collect()
+    };
+    welch_t(&class_times(false), &class_times(true))
+}
+
+fn modulus() -> UnsignedInteger<NUM_LIMBS> {
+    UnsignedInteger::<NUM_LIMBS>::from_hex_unchecked(
+        "73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001"
+    )
+}
+
+#[test]
+#[ignore]
+fn harness_detects_leaky_power_mod() {
+    let (modulus, base) = (modulus(), UnsignedInteger::from_u64(5));
+    let t = leakage_t_statistic(UnsignedInteger::from_u64(1), |exp| power_mod(base, exp, &modulus));
+    println!("power_mod: t = {t:.2}");
+    assert!(t.abs() > T_THRESHOLD);
+}
+
+#[test]
+#[ignore]
+fn power_mod_ct_has_no_detectable_leakage() {
+    let (modulus, base) = (modulus(), UnsignedInteger::from_u64(5));
+    let t = leakage_t_statistic(UnsignedInteger::from_u64(1), |exp| power_mod_ct(base, exp, &modulus));
+    println!("power_mod_ct: t = {t:.2}");
+    assert!(t.abs() < T_THRESHOLD);
+}
@@ -7,6 +7,7 @@ use lambdaworks_math::elliptic_curve::{
         }
 };
 use lambdaworks_math::unsigned_integer::element::UnsignedInteger;
+use number_theory::is_prime_bpsw;
 use random::random_integer_in_range;
 
 const BLS12381_LIMBS: usize = 4;
@@ -53,6 +54,10 @@ impl<T: IsEllipticCurve, const NUM_LIMBS: usize> DiffieHellman<T, NUM_LIMBS> {
 
 
 fn main() {
+    // the subgroup orders must be prime
+    assert!(is_prime_bpsw(&BLS12381_SUBGROUP_ORDER));
+    assert!(is_prime_bpsw(&BN254_SUBGROUP_ORDER));
+
     // Diffie-Hellman with BLS12_384 curve
     let mut alice = DiffieHellman::<BLS12381Curve, BLS12381_LIMBS>::new(BLS12381_SUBGROUP_ORDER);
     let mut bob = DiffieHellman::<BLS12381Curve, BLS12381_LIMBS>::new(BLS12381_SUBGROUP_ORDER);
@@ -1,5 +1,7 @@
 use std::collections::HashSet;
 use lambdaworks_math::field::fields::u64_prime_field::U64FieldElement;
+use lambdaworks_math::unsigned_integer::element::UnsignedInteger;
+use number_theory::legendre;
 
 const MODULUS: u64 = 17;
 // pub type F17 = U64PrimeField<MODULUS>;
@@ -47,4 +49,15 @@ fn main() {
         }
         println!("Multiplicative subgroup generated by {} is: {:?}", x, subgroup);
     };
+
+    //
+    // get the squares of the prime field with the Legendre symbol
+    //
+    println!("Quadratic residues of finite field of modulus {}", MODULUS);
+    let modulus = UnsignedInteger::<1>::from_u64(MODULUS);
+    let (squares, non_squares): (Vec<u64>, Vec<u64>) = (1..MODULUS).partition(
+        |x| legendre(&UnsignedInteger::<1>::from_u64(*x), &modulus) == 1
+    );
+    println!("Squares modulo {}: {:?}", MODULUS, squares);
+    println!("Non squares modulo {}: {:?}", MODULUS, non_squares);
 }
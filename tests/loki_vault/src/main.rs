@@ -22,7 +22,11 @@ use lambdaworks_math::{
     polynomial::Polynomial,
     unsigned_integer::element::UnsignedInteger,
 };
-use number_theory::power_mod;
+use lambdaworks_math::traits::AsBytes;
+use number_theory::{
+    dlog::{baby_step_giant_step, LambdaworksGroup},
+    poly, root_of_unity, MontgomeryContext,
+};
 
 type G1Point = ShortWeierstrassProjectivePoint<BLS12381Curve>;
 type G2Point = ShortWeierstrassProjectivePoint<BLS12381TwistCurve>;
@@ -74,28 +78,24 @@ fn main() {
     // let big_number = UnsignedInteger::<6>::from_limbs([0, 0, 0, 0, 0, 2]);
     // let y = Fq::new(big_number);
 
+    // an honest proof of p(1) = 3 needs 1 to be a root of p(x) - 3
+    let shifted = p.clone() - Polynomial::new(&[FrElement::from(3)]);
+    let degrees: Vec<usize> = poly::factor(&shifted).iter().map(|(factor, _)| factor.degree()).collect();
+    println!("p(x) - 3 factors into irreducibles of degrees {:?}", degrees);
+    if !poly::roots(&shifted).contains(&FrElement::from(1)) {
+        println!("p(1) != 3, so the only way to pass verification is a fake proof");
+    }
+
     // TO DO: Make your own fake proof
     let g1 = &srs.powers_main_group[0];
     let alpha_g1 = &srs.powers_main_group[1];
 
-    let g2 = &srs.powers_secondary_group[0];
-    let alpha_g2 = &srs.powers_secondary_group[1];
-
-    // try obtaining the secret number alpha via brute force.to_hex
-    println!("Trying by brute force up to 1000 tries...");
-    let mut alpha = 1_u64;
-    while alpha < 1000 {
-        let point1 = &g1.operate_with_self(alpha);
-        if point1 == alpha_g1 {
-            break
-        }
-        let point2 = &g2.operate_with_self(alpha);
-        if point2 == alpha_g2 {
-            break
-        }
-        alpha += 1;
+    // try obtaining the secret number alpha via baby-step giant-step.
+    println!("Trying baby-step giant-step up to 2^20...");
+    let group = LambdaworksGroup::new(|point: &G1Point| point.to_affine().as_bytes());
+    if baby_step_giant_step::<4, _>(&group, g1, alpha_g1, 1 << 20).is_none() {
+        println!("Brute force is not the way...");
     }
-    println!("Brute force is not the way...");
 
     // check if a power of the secret number alpha times g1 equals g1.
 
@@ -115,25 +115,31 @@ fn main() {
 
     // find a 64-th primitive root of one modulo r
     println!("Finding a 64-th primitive root of unity modulo r...");
-    let r = UnsignedInteger::<8>::from_hex_unchecked("0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001");
-    let one = UnsignedInteger::<8>::from(1_u64);
-    let sixtyfour = UnsignedInteger::<8>::from(64_u64);
-    let (cofactor, _) = (r - one).div_rem(&sixtyfour);
-    let random_number =  UnsignedInteger::<8>::from(5_u64);    // check with sage that it is appropriate
-    let primitive_root = power_mod(random_number, cofactor, &r);    // check with sage that it has order 64
+    let r = UnsignedInteger::<4>::from_hex_unchecked("0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001");
+    let ctx = MontgomeryContext::new(&r).unwrap();
+    // factorization of r - 1
+    let factorization: Vec<(UnsignedInteger<4>, u32)> = [
+        (2, 32), (3, 1), (11, 1), (19, 1), (10177, 1), (125527, 1), (859267, 1), (906349, 2),
+        (2508409, 1), (2529403, 1), (52437899, 1), (254760293, 2),
+    ].iter().map(|(p, e)| (UnsignedInteger::<4>::from(*p as u64), *e)).collect();
+    let sixtyfour = UnsignedInteger::<4>::from(64_u64);
+    let primitive_root = root_of_unity(&sixtyfour, &r, &factorization)
+        .expect("64 divides r - 1");
     println!("Primitive root found! : {:?}", primitive_root.to_hex());
+    let primitive_root = ctx.to_mont(&primitive_root);
 
     println!("Trying to find alpha as one of the 64 roots of unity...");
     // let mut point: ShortWeierstrassProjectivePoint::<BLS12381Curve>;
-    // let mut candidate = UnsignedInteger::<8>::from(0_u64);
+    // let mut candidate = UnsignedInteger::<4>::from(0_u64);
     let mut k = 1;
+    let mut candidate_mont = primitive_root;
     let alpha = loop {
-        let pow = UnsignedInteger::<8>::from(k as u64);
-        let candidate = power_mod(primitive_root, pow, &r);
+        let candidate = ctx.from_mont(&candidate_mont);
         let point = &g1.operate_with_self(candidate);
         if point == alpha_g1 {
             break candidate
         }
+        candidate_mont = ctx.mul(&candidate_mont, &primitive_root);
         k += 1;
         if k > 64 {
             panic!("Secret number not found.");
@@ -145,7 +151,6 @@ fn main() {
 
     // Create fake evaluation point
     println!("Creating fake evaluation proof...");
-    let alpha = UnsignedInteger::<4>::from_hex_unchecked(&alpha.to_hex());  // ugly way of reducing from 8 to 4 limbs
     let fake_p_eval_at_alpha = p.evaluate(&FrElement::from(&alpha));
     let num = fake_p_eval_at_alpha - FrElement::from(3);
     let den = FrElement::from(&alpha) - FrElement::from(1);
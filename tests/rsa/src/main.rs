@@ -1,6 +1,8 @@
 use blake2::{Blake2s256, Digest};
 use lambdaworks_math::{traits::ByteConversion, unsigned_integer::element::UnsignedInteger};
-use number_theory::{power_mod, extended_euclidean_algorithm};
+use number_theory::{
+    carmichael_lambda_from_factorization, euler_phi_from_factorization, mod_inverse, wiener_attack, MontgomeryContext,
+};
 use random::random_prime_from_bitsize;
 
 const LIMB_SIZE_BIT: usize = 64;
@@ -19,31 +21,24 @@ struct Rsa<const NUM_LIMBS: usize> {
 impl<const NUM_LIMBS: usize> Rsa<NUM_LIMBS> {
 
     fn new() -> Self {
-        let zero = UnsignedInteger::<NUM_LIMBS>::from_u64(0);
-        let one = UnsignedInteger::<NUM_LIMBS>::from_u64(1);
-
         // generate two random primes of bitsize
-        //      NUM_LIMBS * LIMB_SIZE_BIT / 4 
-        // to prevent UnsignedInteger overflow.
-        let bit_size = NUM_LIMBS * LIMB_SIZE_BIT / 4;
+        //      NUM_LIMBS * LIMB_SIZE_BIT / 2
+        // so that the modulus uses the full size of UnsignedInteger.
+        let bit_size = NUM_LIMBS * LIMB_SIZE_BIT / 2;
         let p = random_prime_from_bitsize::<NUM_LIMBS>(bit_size);
         let q = random_prime_from_bitsize::<NUM_LIMBS>(bit_size);
 
         let modulus = p * q;
-        let euler_phi = (p - one) * (q - one);
+        // lambda(n) = lcm(p - 1, q - 1) divides euler_phi(n), and any inverse
+        // of the encryption exponent modulo lambda(n) decrypts.
+        let lambda = carmichael_lambda_from_factorization(&[(p, 1), (q, 1)]);
 
-        // check 65537 < euler_phi and the they have no common factors.
-        // Since 65537 is prime, it is sufficient to check that is not a
-        // multiple of 65537.
+        // check 65537 < lambda and the they have no common factors,
+        // in which case the decryption exponent is its inverse.
         let encryption_exp = UnsignedInteger::<NUM_LIMBS>::from_u64(65537);
-        assert!(encryption_exp < euler_phi);
-        let (_, rem) = euler_phi.div_rem(&encryption_exp);
-        assert!(rem != zero);
-        
-        let (_, decryption_exp, _) = extended_euclidean_algorithm(
-            encryption_exp,
-            euler_phi
-        );
+        assert!(encryption_exp < lambda);
+        let decryption_exp = mod_inverse(encryption_exp, &lambda)
+            .expect("65537 divides lambda");
 
         Self { 
             encryption_exp,
@@ -52,6 +47,30 @@ impl<const NUM_LIMBS: usize> Rsa<NUM_LIMBS> {
         }
     }
 
+    // Weak keys, with a decryption exponent below n^(1/4) / 3 that
+    // Wiener's attack recovers from the public key. As in Wiener's setting,
+    // the exponents are inverses modulo euler_phi.
+    fn new_with_small_decryption_exp() -> Self {
+        let bit_size = NUM_LIMBS * LIMB_SIZE_BIT / 2;
+        let p = random_prime_from_bitsize::<NUM_LIMBS>(bit_size);
+        let q = random_prime_from_bitsize::<NUM_LIMBS>(bit_size);
+
+        let modulus = p * q;
+        let euler_phi = euler_phi_from_factorization(&[(p, 1), (q, 1)]);
+
+        // a quarter of the modulus size, minus two bits, stays below n^(1/4) / 3.
+        loop {
+            let decryption_exp = random_prime_from_bitsize::<NUM_LIMBS>(NUM_LIMBS * LIMB_SIZE_BIT / 4 - 2);
+            if let Some(encryption_exp) = mod_inverse(decryption_exp, &euler_phi) {
+                return Self {
+                    encryption_exp,
+                    decryption_exp,
+                    modulus
+                };
+            }
+        }
+    }
+
     fn public_key(&self) -> PubKey<NUM_LIMBS> {
         PubKey(self.encryption_exp, self.modulus)
     }
@@ -74,7 +93,9 @@ impl<const NUM_LIMBS: usize> Rsa<NUM_LIMBS> {
         ).unwrap();
         assert!(plaintext_as_integer < modulus);
 
-        power_mod(plaintext_as_integer, exponent, &modulus).to_bytes_be()
+        let ctx = MontgomeryContext::new(&modulus).unwrap();
+        let encrypted_integer = ctx.pow(&ctx.to_mont(&plaintext_as_integer), &exponent);
+        ctx.from_mont(&encrypted_integer).to_bytes_be()
     }
 
     fn decrypt(&self, encrypted_bytes: Vec<u8>) -> Vec<u8> {
@@ -82,12 +103,13 @@ impl<const NUM_LIMBS: usize> Rsa<NUM_LIMBS> {
             &encrypted_bytes  
         ).unwrap();
 
-        let decrypted_integer = power_mod(
-            encripted_integer,
-            self.decryption_exp,
-            &self.modulus
+        let ctx = MontgomeryContext::new(&self.modulus).unwrap();
+        // decryption exponent is secret, so exponentiate in constant time.
+        let decrypted_integer = ctx.pow_ct(
+            &ctx.to_mont(&encripted_integer),
+            &self.decryption_exp
         );
-        decrypted_integer.to_bytes_be()
+        ctx.from_mont(&decrypted_integer).to_bytes_be()
     }
 
     // Signature scheme: uses Blake2s256 hash function with digest of 32 bytes
@@ -125,9 +147,9 @@ impl<const NUM_LIMBS: usize> Rsa<NUM_LIMBS> {
 fn main() {
     // Hash function used has a digest of 32 bytes,
     // therefore RSA modulus should be at least 32 bytes.
-    // In this implementation, the modulus is approximately
-    // half of the size of the RSA, which therefore should be 
-    // of at least 64 bytes = 512 bits = 8 limbs.
+    // In this implementation, the modulus has the full
+    // size of the RSA, which therefore should be
+    // of at least 32 bytes = 256 bits = 4 limbs.
     // In the following example 16 limbs are used.
     const NUM_LIMBS: usize = 16;
     type RSA512 = Rsa<NUM_LIMBS>;
@@ -189,4 +211,18 @@ fn main() {
     } else {
         println!("Signature is invalid.");
     }
+
+    //
+    // Wiener's attack on a small decryption exponent //
+    //
+    println!("----------");
+    println!("Generating Eve's keys with a small decryption exponent.");
+    let eve_rsa = RSA512::new_with_small_decryption_exp();
+    let PubKey(encryption_exp, modulus) = eve_rsa.public_key();
+    match wiener_attack(&encryption_exp, &modulus) {
+        Some(decryption_exp) if decryption_exp == eve_rsa.decryption_exp => {
+            println!("Wiener's attack recovered Eve's decryption exponent from the public key.");
+        }
+        _ => println!("Wiener's attack failed."),
+    }
 }